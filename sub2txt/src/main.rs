@@ -1,5 +1,10 @@
 use clap::{App, Arg};
-use std::{error::Error, fs::File};
+use std::{
+    borrow::Cow,
+    error::Error,
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+};
 
 const NAME: &str = env!("CARGO_PKG_NAME");
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -16,24 +21,117 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .short("f")
                 .long("file")
                 .value_name("FILE")
-                .required(true),
+                .help("subtitles file to convert, - for standard input")
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("files")
+                .value_name("FILE")
+                .help("more subtitles files, standard input if none are given")
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .value_name("FILE")
+                .help("write to FILE instead of standard output"),
+        )
+        .arg(
+            Arg::with_name("strip-tags")
+                .long("strip-tags")
+                .help("remove formatting tags such as <i> and {\\b1}"),
+        )
+        .arg(
+            Arg::with_name("decode-entities")
+                .long("decode-entities")
+                .help("decode HTML entities such as &amp;"),
+        )
+        .arg(
+            Arg::with_name("separate")
+                .short("s")
+                .long("separate")
+                .help("print a blank line between the text of each file"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("output format, json for an array of the subtitles with their timing")
+                .possible_values(&["text", "json"])
+                .default_value("text"),
         )
         .get_matches();
 
-    let path = matches.value_of("file").unwrap();
-    let file = File::open(path)?;
+    let mut paths: Vec<&str> = matches
+        .values_of("file")
+        .into_iter()
+        .chain(matches.values_of("files"))
+        .flatten()
+        .collect();
+    if paths.is_empty() {
+        paths.push("-");
+    }
+    let strip_tags = matches.is_present("strip-tags");
+    let decode_entities = matches.is_present("decode-entities");
+    let separate = matches.is_present("separate");
+    let json = matches.value_of("format") == Some("json");
+
+    // open every input first, so a missing one leaves the output untouched
+    let inputs = paths
+        .into_iter()
+        .map(|path| -> io::Result<Box<dyn Read>> {
+            if path == "-" {
+                Ok(Box::new(io::stdin()))
+            } else {
+                Ok(Box::new(File::open(path)?))
+            }
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let stdout = io::stdout();
+    let out: Box<dyn Write> = match matches.value_of("output") {
+        Some(output) => Box::new(File::create(output)?),
+        None => Box::new(stdout.lock()),
+    };
+    let mut out = BufWriter::new(out);
 
-    let parser = subtitles::open(file);
-    for entry in parser {
-        match entry {
-            Ok(sub) => {
-                for line in sub.text {
-                    println!("{}", line);
+    let mut subs = Vec::new();
+    for (index, input) in inputs.into_iter().enumerate() {
+        if separate && !json && index > 0 {
+            writeln!(out)?;
+        }
+        for entry in subtitles::open_auto(input)? {
+            match entry {
+                Ok(mut sub) => {
+                    for line in &mut sub.text {
+                        if strip_tags {
+                            if let Cow::Owned(stripped) = subtitles::strip_tags(line) {
+                                *line = stripped;
+                            }
+                        }
+                        if decode_entities {
+                            *line = subtitles::decode_entities(line);
+                        }
+                    }
+                    if json {
+                        subs.push(sub);
+                    } else {
+                        for line in sub.text {
+                            writeln!(out, "{}", line)?;
+                        }
+                    }
                 }
+                Err(err) => eprintln!("Error: {}", err),
             }
-            Err(err) => eprintln!("Error: {}", err),
         }
     }
+    if json {
+        serde_json::to_writer(&mut out, &subs)?;
+        writeln!(out)?;
+    }
 
+    out.flush()?;
     Ok(())
 }
@@ -0,0 +1,155 @@
+use std::{
+    env, fs,
+    io::Write,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+#[test]
+fn output_file() {
+    let dir = env::temp_dir().join(format!("sub2txt-{}-output", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("movie.srt");
+    let output = dir.join("movie.txt");
+    fs::write(
+        &input,
+        "\
+1
+00:00:01,000 --> 00:00:02,000
+First
+Line
+
+2
+00:00:03,000 --> 00:00:04,000
+Second
+",
+    )
+    .unwrap();
+    fs::write(&output, "stale contents that should be truncated").unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_sub2txt"))
+        .arg("-f")
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .output()
+        .unwrap();
+    assert!(result.status.success());
+    assert!(result.stdout.is_empty());
+
+    assert_eq!(
+        "First\nLine\nSecond\n",
+        fs::read_to_string(&output).unwrap()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn strip_tags() {
+    let dir = env::temp_dir().join(format!("sub2txt-{}-strip-tags", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("movie.srt");
+    fs::write(
+        &input,
+        "\
+1
+00:00:01,000 --> 00:00:02,000
+<i>Hello</i> {\\b1}World
+Tom &amp; Jerry
+",
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        let result = Command::new(env!("CARGO_BIN_EXE_sub2txt"))
+            .arg("-f")
+            .arg(&input)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(result.status.success());
+        String::from_utf8(result.stdout).unwrap()
+    };
+
+    assert_eq!("Hello World\nTom &amp; Jerry\n", run(&["--strip-tags"]));
+    assert_eq!(
+        "Hello World\nTom & Jerry\n",
+        run(&["--strip-tags", "--decode-entities"])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn multiple_inputs() {
+    let dir = env::temp_dir().join(format!("sub2txt-{}-multiple", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let first = dir.join("first.srt");
+    let second = dir.join("second.srt");
+    fs::write(&first, "1\n00:00:01,000 --> 00:00:02,000\nFirst\n").unwrap();
+    fs::write(&second, "1\n00:00:01,000 --> 00:00:02,000\nSecond\n").unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sub2txt"))
+        .arg("--separate")
+        .arg(&first)
+        .arg("-")
+        .arg(&second)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"1\n00:00:01,000 --> 00:00:02,000\nPiped\n")
+        .unwrap();
+    let result = child.wait_with_output().unwrap();
+    assert!(result.status.success());
+
+    assert_eq!(
+        "First\n\nPiped\n\nSecond\n",
+        String::from_utf8(result.stdout).unwrap()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn json_format() {
+    let dir = env::temp_dir().join(format!("sub2txt-{}-json", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("movie.srt");
+    fs::write(
+        &input,
+        "\
+1
+00:00:01,000 --> 00:00:02,500
+First
+Line
+",
+    )
+    .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_sub2txt"))
+        .arg("--format")
+        .arg("json")
+        .arg(&input)
+        .output()
+        .unwrap();
+    assert!(result.status.success());
+
+    let subs: Vec<subtitles::SubRip> = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(
+        vec![subtitles::SubRip::from_parts(
+            1,
+            Duration::from_millis(1000),
+            Duration::from_millis(2500),
+            "First\nLine"
+        )],
+        subs
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,1090 @@
+use ansi_term::{
+    ANSIString,
+    Color::{self, Blue, Green, Red, Yellow},
+    Style,
+};
+use encoding_rs::{Encoding, UTF_8};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    error,
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    result,
+};
+use subfind::find_matches;
+use subtitles::SubRip;
+
+type Result<T> = result::Result<T, Box<dyn error::Error>>;
+
+pub struct Config<'a> {
+    pub regex: Regex,
+    pub paths: Vec<&'a str>,
+    pub unique: bool,
+    pub strip_tags: bool,
+    pub match_filename: bool,
+    pub json: bool,
+    pub context: usize,
+    pub extensions: Vec<&'a str>,
+    pub follow: bool,
+    pub only_matching: bool,
+    pub replace: Option<&'a str>,
+    pub color: bool,
+    pub invert: bool,
+    pub files_with_matches: bool,
+    pub max_count: Option<usize>,
+}
+
+pub fn run(config: Config) -> Result<()> {
+    let stdout = io::stdout();
+    let mut finder = Finder::new(&config, stdout.lock());
+
+    for path in &config.paths {
+        if *path == "-" && config.replace.is_some() {
+            if let Some((subs, _, _)) = finder.replace(io::stdin(), None) {
+                subtitles::write(&mut finder.out, subs)?;
+            }
+        } else if *path == "-" {
+            finder.find(io::stdin(), None)?;
+        } else {
+            finder.find_in_path(path)?;
+        }
+    }
+    Ok(())
+}
+
+struct Finder<'a, W: Write> {
+    config: &'a Config<'a>,
+    out: W,
+    seen: HashSet<String>,
+    // canonical paths of the directories searched while following symlinks
+    visited: HashSet<PathBuf>,
+    // matching lines printed from the file being searched
+    matched_lines: usize,
+}
+
+impl<'a, W: Write> Finder<'a, W> {
+    fn new(config: &'a Config<'a>, out: W) -> Self {
+        Finder {
+            config,
+            out,
+            seen: HashSet::new(),
+            visited: HashSet::new(),
+            matched_lines: 0,
+        }
+    }
+
+    /// Paint `text` in `color`, unless colors are disabled.
+    fn paint<'s>(&self, color: Color, text: &'s str) -> ANSIString<'s> {
+        if self.config.color {
+            color.paint(text)
+        } else {
+            Style::new().paint(text)
+        }
+    }
+
+    /// Whether `--max-count` matching lines were printed from this file.
+    fn max_count_reached(&self) -> bool {
+        self.config
+            .max_count
+            .is_some_and(|max_count| self.matched_lines >= max_count)
+    }
+
+    /// Search the file or directory at `path`, given on the command line.
+    ///
+    /// `path` itself is searched even if it is a symbolic link or a file
+    /// without one of the searched extensions.
+    fn find_in_path(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.search_path(path.as_ref(), true)
+    }
+
+    fn search_path(&mut self, path: &Path, explicit: bool) -> Result<()> {
+        let follow = explicit || self.config.follow;
+        let metadata = if follow {
+            fs::metadata(path)?
+        } else {
+            fs::symlink_metadata(path)?
+        };
+        let file_type = metadata.file_type();
+
+        if file_type.is_dir() {
+            // a directory can't be hard linked, so its canonical path
+            // identifies it, and a second visit means a symlink loop
+            if self.config.follow && !self.visited.insert(fs::canonicalize(path)?) {
+                return Ok(());
+            }
+            for entry in fs::read_dir(path)? {
+                self.search_path(&entry?.path(), false)?;
+            }
+        } else if file_type.is_file() && (explicit || self.has_extension(path)) {
+            if self.config.replace.is_some() {
+                return self.replace_in_file(path);
+            }
+            if self.config.json {
+                self.print_file_name_json(path)?;
+            } else if !self.config.files_with_matches {
+                self.print_file_name(path)?;
+            }
+            self.find(File::open(path)?, Some(path))?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the matches in the file at `path`, rewriting it in place.
+    ///
+    /// The file is left untouched if nothing matches or it fails to parse.
+    /// Otherwise it is rewritten with [`subtitles::rewrite_path`], which
+    /// keeps its line endings, encoding, BOM and permissions.
+    fn replace_in_file(&mut self, path: &Path) -> Result<()> {
+        let original = fs::read(path)?;
+        if let Some((subs, true, encoding)) = self.replace(&original[..], Some(path)) {
+            subtitles::rewrite_path(path, &original, encoding, subs)?;
+        }
+        Ok(())
+    }
+
+    /// Parse `subtitle` and replace the matches in its text.
+    ///
+    /// Returns the subtitles, whether anything was replaced and the
+    /// encoding of `subtitle`, or `None` after printing the error if
+    /// `subtitle` fails to parse.
+    fn replace<T: Read>(
+        &self,
+        subtitle: T,
+        path: Option<&Path>,
+    ) -> Option<(Vec<SubRip>, bool, &'static Encoding)> {
+        let mut parser = subtitles::open(subtitle);
+        let mut subs = match parser.by_ref().collect::<result::Result<Vec<_>, _>>() {
+            Ok(subs) => subs,
+            Err(err) => {
+                match path {
+                    Some(path) => {
+                        eprintln!("{}: {}: {}", self.paint(Red, "Error"), path.display(), err)
+                    }
+                    None => eprintln!("{}: {}", self.paint(Red, "Error"), err),
+                }
+                return None;
+            }
+        };
+        let encoding = parser.encoding().unwrap_or(UTF_8);
+
+        let regex = &self.config.regex;
+        let replacement = self.config.replace.unwrap_or_default();
+        let mut replaced = false;
+        for line in subs.iter_mut().flat_map(|sub| &mut sub.text) {
+            if regex.is_match(line) {
+                *line = regex.replace_all(line, replacement).into_owned();
+                replaced = true;
+            }
+        }
+        Some((subs, replaced, encoding))
+    }
+
+    /// Whether `path` has one of the extensions searched in directories.
+    fn has_extension(&self, path: &Path) -> bool {
+        let extension = match path.extension().and_then(|ext| ext.to_str()) {
+            Some(extension) => extension,
+            None => return false,
+        };
+        self.config
+            .extensions
+            .iter()
+            .any(|ext| ext.trim_start_matches('.').eq_ignore_ascii_case(extension))
+    }
+
+    /// Print the file stem, highlighting it if `--match-filename` matched it.
+    fn print_file_name(&mut self, path: &Path) -> io::Result<()> {
+        if let Some(stem) = path.file_stem() {
+            if let Some(stem_str) = stem.to_str() {
+                let regex = &self.config.regex;
+                if self.config.match_filename && regex.is_match(stem_str) {
+                    let mut last_match = 0;
+                    for reg_match in regex.find_iter(stem_str) {
+                        let unmatched = &stem_str[last_match..reg_match.start()];
+                        let matched = reg_match.as_str();
+                        write!(
+                            self.out,
+                            "{}{}",
+                            self.paint(Blue, unmatched),
+                            self.paint(Green, matched)
+                        )?;
+
+                        last_match = reg_match.end();
+                    }
+                    writeln!(
+                        self.out,
+                        "{} (file name match)",
+                        self.paint(Blue, &stem_str[last_match..])
+                    )?;
+                } else {
+                    writeln!(self.out, "{}", self.paint(Blue, stem_str))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Print a file name match as a single line of JSON.
+    ///
+    /// The object has the following shape:
+    ///
+    /// ```json
+    /// {
+    ///   "type": "file_name",
+    ///   "path": "dir/file.srt",
+    ///   "file_name": "file",
+    ///   "matches": [{ "start": 0, "end": 4 }]
+    /// }
+    /// ```
+    ///
+    /// `type` tells these objects apart from the matches of
+    /// [`print_json`](Self::print_json) in the same output. Match offsets
+    /// are byte offsets into `file_name`, the file stem.
+    fn print_file_name_json(&mut self, path: &Path) -> io::Result<()> {
+        if !self.config.match_filename {
+            return Ok(());
+        }
+
+        if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+            let matches: Vec<Value> = self
+                .config
+                .regex
+                .find_iter(stem)
+                .map(|reg_match| json!({ "start": reg_match.start(), "end": reg_match.end() }))
+                .collect();
+
+            if !matches.is_empty() {
+                let object = json!({
+                    "type": "file_name",
+                    "path": path.to_string_lossy(),
+                    "file_name": stem,
+                    "matches": matches,
+                });
+                writeln!(self.out, "{}", object)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn find<T: Read>(&mut self, subtitle: T, path: Option<&Path>) -> io::Result<()> {
+        self.matched_lines = 0;
+        let strip_tags = self.config.strip_tags;
+        let error = self.paint(Red, "Error");
+        let mut parser = subtitles::open_auto(subtitle)?.filter_map(|entry| match entry {
+            Ok(mut sub) => {
+                if strip_tags {
+                    for line in &mut sub.text {
+                        if let Cow::Owned(stripped) = subtitles::strip_tags(line) {
+                            *line = stripped;
+                        }
+                    }
+                }
+                Some(sub)
+            }
+            Err(err) => {
+                eprintln!("{}: {}", error, err);
+                None
+            }
+        });
+
+        if self.config.files_with_matches {
+            // stop reading at the first match
+            let config = self.config;
+            let has_match = parser.any(|sub| {
+                sub.text
+                    .iter()
+                    .any(|line| config.regex.is_match(line) != config.invert)
+            });
+            if has_match {
+                let path = path.map_or(Cow::Borrowed("(standard input)"), |path| {
+                    path.to_string_lossy()
+                });
+                writeln!(self.out, "{}", self.paint(Blue, &path))?;
+            }
+        } else if self.config.json {
+            let subs: Vec<SubRip> = parser.collect();
+            for index in 0..subs.len() {
+                self.print_json(&subs, index, path)?;
+            }
+        } else if self.config.context == 0 {
+            for sub in parser {
+                // stop reading once enough lines were printed
+                if self.max_count_reached() {
+                    break;
+                }
+                self.print_matches(&sub)?;
+            }
+        } else {
+            let subs: Vec<SubRip> = parser.collect();
+            self.print_with_context(&subs)?;
+        }
+        Ok(())
+    }
+
+    /// Print the matches in `subs` along with `context` subtitles around
+    /// each of them, like `grep -C`.
+    ///
+    /// Overlapping groups of subtitles are merged, and separate groups are
+    /// divided by a `--` line.
+    fn print_with_context(&mut self, subs: &[SubRip]) -> io::Result<()> {
+        let config = self.config;
+        let is_match = |sub: &SubRip| sub.text.iter().any(|line| config.regex.is_match(line));
+
+        let mut groups: Vec<(usize, usize)> = Vec::new();
+        for (index, _) in subs.iter().enumerate().filter(|(_, sub)| is_match(sub)) {
+            let start = index.saturating_sub(config.context);
+            let end = subs.len().min(index + 1 + config.context);
+            match groups.last_mut() {
+                Some(last) if start <= last.1 => last.1 = end,
+                _ => groups.push((start, end)),
+            }
+        }
+
+        for (group, &(start, end)) in groups.iter().enumerate() {
+            if group > 0 {
+                writeln!(self.out, "--")?;
+            }
+            for sub in &subs[start..end] {
+                if is_match(sub) {
+                    self.print_matches(sub)?;
+                } else {
+                    self.print_context(sub)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Print each match in `subs[index]` as a single line of JSON.
+    ///
+    /// The objects have the following shape:
+    ///
+    /// ```json
+    /// {
+    ///   "type": "match",
+    ///   "path": "file.srt",
+    ///   "position": 2,
+    ///   "start": "00:00:01,000",
+    ///   "end": "00:00:02,000",
+    ///   "line": 0,
+    ///   "text": "line",
+    ///   "match": { "start": 0, "end": 4 },
+    ///   "before": [{ "position": 1, "start": "...", "end": "...", "text": [] }],
+    ///   "after": [{ "position": 3, "start": "...", "end": "...", "text": [] }]
+    /// }
+    /// ```
+    ///
+    /// `path` is `null` for standard input. `text` is the matching line, at
+    /// index `line` of the subtitle text, and the match offsets are byte
+    /// offsets into it. `before` and `after` hold up to `context`
+    /// neighbouring subtitles in file order.
+    fn print_json(&mut self, subs: &[SubRip], index: usize, path: Option<&Path>) -> io::Result<()> {
+        let sub = &subs[index];
+        let context = self.config.context;
+        let before: Vec<Value> = subs[index.saturating_sub(context)..index]
+            .iter()
+            .map(cue_json)
+            .collect();
+        let after: Vec<Value> = subs[index + 1..subs.len().min(index + 1 + context)]
+            .iter()
+            .map(cue_json)
+            .collect();
+
+        for found in find_matches(&self.config.regex, sub) {
+            let object = json!({
+                "type": "match",
+                "path": path.map(|path| path.to_string_lossy()),
+                "position": sub.position,
+                "start": sub.start.to_string(),
+                "end": sub.end.to_string(),
+                "line": found.line,
+                "text": found.text,
+                "match": { "start": found.range.start, "end": found.range.end },
+                "before": before,
+                "after": after,
+            });
+            writeln!(self.out, "{}", object)?;
+        }
+        Ok(())
+    }
+
+    /// Print the matching lines of `subtitle`, each after its start time.
+    fn print_matches(&mut self, subtitle: &SubRip) -> io::Result<()> {
+        let start = subtitle.start.to_string();
+
+        if self.config.invert {
+            for line in &subtitle.text {
+                if self.config.regex.is_match(line)
+                    || self.config.unique && !self.seen.insert(line.to_string())
+                {
+                    continue;
+                }
+                if self.max_count_reached() {
+                    break;
+                }
+                writeln!(self.out, "{}  {}", self.paint(Yellow, &start), line)?;
+                self.matched_lines += 1;
+            }
+            return Ok(());
+        }
+
+        let matches = find_matches(&self.config.regex, subtitle);
+
+        if self.config.only_matching {
+            for line_matches in matches.chunk_by(|a, b| a.line == b.line) {
+                if self.max_count_reached() {
+                    break;
+                }
+                let mut printed = false;
+                for found in line_matches {
+                    let matched = &found.text[found.range.clone()];
+                    if self.config.unique && !self.seen.insert(matched.to_string()) {
+                        continue;
+                    }
+                    writeln!(self.out, "{}", self.paint(Green, matched))?;
+                    printed = true;
+                }
+                if printed {
+                    self.matched_lines += 1;
+                }
+            }
+            return Ok(());
+        }
+
+        for line_matches in matches.chunk_by(|a, b| a.line == b.line) {
+            let line = line_matches[0].text;
+            if self.config.unique && !self.seen.insert(line.to_string()) {
+                continue;
+            }
+            if self.max_count_reached() {
+                break;
+            }
+            self.matched_lines += 1;
+
+            write!(self.out, "{}  ", self.paint(Yellow, &start))?;
+
+            let mut last_match = 0;
+            for found in line_matches {
+                let unmatched = &line[last_match..found.range.start];
+                let matched = &line[found.range.clone()];
+                write!(self.out, "{}{}", unmatched, self.paint(Green, matched))?;
+
+                last_match = found.range.end;
+            }
+            writeln!(self.out, "{}", &line[last_match..])?;
+        }
+        Ok(())
+    }
+
+    /// Print every line of `subtitle` without highlighting.
+    fn print_context(&mut self, subtitle: &SubRip) -> io::Result<()> {
+        let start = subtitle.start.to_string();
+        for line in &subtitle.text {
+            writeln!(self.out, "{}  {}", self.paint(Yellow, &start), line)?;
+        }
+        Ok(())
+    }
+}
+
+fn cue_json(sub: &SubRip) -> Value {
+    json!({
+        "position": sub.position,
+        "start": sub.start.to_string(),
+        "end": sub.end.to_string(),
+        "text": sub.text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, path::PathBuf, process};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("subfind-{}-{}", process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A configuration searching for `pattern` with the default options.
+    fn config(pattern: &str) -> Config<'static> {
+        Config {
+            regex: Regex::new(pattern).unwrap(),
+            paths: Vec::new(),
+            unique: false,
+            strip_tags: false,
+            match_filename: false,
+            json: false,
+            context: 0,
+            extensions: vec!["srt"],
+            follow: false,
+            only_matching: false,
+            replace: None,
+            color: true,
+            invert: false,
+            files_with_matches: false,
+            max_count: None,
+        }
+    }
+
+    fn search(config: &Config, paths: &[&Path]) -> String {
+        let mut out = Vec::new();
+        let mut finder = Finder::new(config, &mut out);
+        for path in paths {
+            finder.find_in_path(path).unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn unique_lines() {
+        let dir = temp_dir("unique");
+        let first = dir.join("first.srt");
+        let second = dir.join("second.srt");
+        fs::write(
+            &first,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+Hello there
+
+2
+00:00:01,000 --> 00:00:02,000
+General Kenobi",
+        )
+        .unwrap();
+        fs::write(
+            &second,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+Hello there
+
+2
+00:00:01,000 --> 00:00:02,000
+Hello again",
+        )
+        .unwrap();
+
+        let config = Config {
+            unique: true,
+            ..config("Hello")
+        };
+        let output = search(&config, &[&first, &second]);
+
+        let expected = format!(
+            "{}\n{}  {}{}\n{}\n{}  {}{}\n",
+            Blue.paint("first"),
+            Yellow.paint("00:00:00,000"),
+            Green.paint("Hello"),
+            " there",
+            Blue.paint("second"),
+            Yellow.paint("00:00:01,000"),
+            Green.paint("Hello"),
+            " again",
+        );
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn timecode() {
+        let dir = temp_dir("timecode");
+        let path = dir.join("movie.srt");
+        fs::write(
+            &path,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+Nothing to see
+
+2
+01:04:00,705 --> 01:04:02,145
+It's only after",
+        )
+        .unwrap();
+
+        let config = config("only");
+        let output = search(&config, &[&path]);
+
+        let expected = format!(
+            "{}\n{}  It's {} after\n",
+            Blue.paint("movie"),
+            Yellow.paint("01:04:00,705"),
+            Green.paint("only"),
+        );
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn only_matching() {
+        let dir = temp_dir("only-matching");
+        let path = dir.join("week.srt");
+        fs::write(
+            &path,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+See you Monday Tuesday",
+        )
+        .unwrap();
+
+        let config = Config {
+            only_matching: true,
+            ..config(r"\w+day")
+        };
+        let output = search(&config, &[&path]);
+
+        let expected = format!(
+            "{}\n{}\n{}\n",
+            Blue.paint("week"),
+            Green.paint("Monday"),
+            Green.paint("Tuesday"),
+        );
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn replace() {
+        let dir = temp_dir("replace");
+        let path = dir.join("colours.srt");
+        let untouched = dir.join("untouched.srt");
+        fs::write(
+            &path,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+What colour is it?
+
+2
+00:00:01,000 --> 00:00:02,000
+All the colours
+of the rainbow",
+        )
+        .unwrap();
+        fs::write(&untouched, "1\r\n00:00:00,000 --> 00:00:01,000\r\nGrey\r\n").unwrap();
+
+        let config = Config {
+            replace: Some("color"),
+            ..config("colour")
+        };
+        let output = search(&config, &[&dir]);
+
+        assert!(output.is_empty());
+        assert_eq!(
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+What color is it?
+
+2
+00:00:01,000 --> 00:00:02,000
+All the colors
+of the rainbow
+
+",
+            fs::read_to_string(&path).unwrap()
+        );
+        assert_eq!(
+            "1\r\n00:00:00,000 --> 00:00:01,000\r\nGrey\r\n",
+            fs::read_to_string(&untouched).unwrap()
+        );
+        assert_eq!(2, fs::read_dir(&dir).unwrap().count());
+    }
+
+    #[test]
+    fn replace_keeps_format() {
+        let dir = temp_dir("replace-format");
+        let crlf = dir.join("crlf.srt");
+        let utf16 = dir.join("utf16.srt");
+        fs::write(
+            &crlf,
+            "\u{FEFF}1\r\n00:00:00,000 --> 00:00:01,000\r\nWhat colour?\r\n",
+        )
+        .unwrap();
+        let mut contents = vec![0xFF, 0xFE];
+        for unit in "1\n00:00:00,000 --> 00:00:01,000\nCafé colour\n".encode_utf16() {
+            contents.extend_from_slice(&unit.to_le_bytes());
+        }
+        fs::write(&utf16, contents).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&crlf, fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        let config = Config {
+            replace: Some("color"),
+            ..config("colour")
+        };
+        search(&config, &[&dir]);
+
+        assert_eq!(
+            "\u{FEFF}1\r\n00:00:00,000 --> 00:00:01,000\r\nWhat color?\r\n\r\n",
+            fs::read_to_string(&crlf).unwrap()
+        );
+        let mut expected = vec![0xFF, 0xFE];
+        for unit in "1\n00:00:00,000 --> 00:00:01,000\nCafé color\n\n".encode_utf16() {
+            expected.extend_from_slice(&unit.to_le_bytes());
+        }
+        assert_eq!(expected, fs::read(&utf16).unwrap());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&crlf).unwrap().permissions().mode();
+            assert_eq!(0o600, mode & 0o777);
+        }
+    }
+
+    #[test]
+    fn no_color() {
+        let dir = temp_dir("no-color");
+        let path = dir.join("movie.srt");
+        fs::write(
+            &path,
+            "\
+1
+01:04:00,705 --> 01:04:02,145
+It's only after",
+        )
+        .unwrap();
+
+        let config = Config {
+            color: false,
+            ..config("only")
+        };
+        let output = search(&config, &[&path]);
+
+        assert_eq!("movie\n01:04:00,705  It's only after\n", output);
+    }
+
+    #[test]
+    fn invert_match() {
+        let dir = temp_dir("invert");
+        let path = dir.join("movie.srt");
+        fs::write(
+            &path,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+ANNA: Hello there
+Hello again",
+        )
+        .unwrap();
+
+        let config = Config {
+            color: false,
+            invert: true,
+            ..config("^[A-Z]+:")
+        };
+        let output = search(&config, &[&path]);
+
+        assert_eq!("movie\n00:00:00,000  Hello again\n", output);
+    }
+
+    #[test]
+    fn files_with_matches() {
+        let dir = temp_dir("files-with-matches");
+        let first = dir.join("first.srt");
+        let second = dir.join("second.srt");
+        fs::write(
+            &first,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+Hello there
+
+2
+00:00:01,000 --> 00:00:02,000
+Hello again",
+        )
+        .unwrap();
+        fs::write(
+            &second,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+General Kenobi",
+        )
+        .unwrap();
+
+        let config = Config {
+            color: false,
+            files_with_matches: true,
+            ..config("Hello")
+        };
+        let output = search(&config, &[&first, &second]);
+
+        assert_eq!(format!("{}\n", first.display()), output);
+    }
+
+    #[test]
+    fn max_count() {
+        let dir = temp_dir("max-count");
+        let path = dir.join("movie.srt");
+        fs::write(
+            &path,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+Hello there
+Hello again
+
+2
+00:00:01,000 --> 00:00:02,000
+Hello, it's me",
+        )
+        .unwrap();
+
+        let config = Config {
+            color: false,
+            max_count: Some(1),
+            ..config("Hello")
+        };
+        let output = search(&config, &[&path, &path]);
+
+        assert_eq!(
+            "movie\n00:00:00,000  Hello there\nmovie\n00:00:00,000  Hello there\n",
+            output
+        );
+    }
+
+    #[test]
+    fn context() {
+        let dir = temp_dir("context");
+        let path = dir.join("movie.srt");
+        fs::write(
+            &path,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+Before
+
+2
+00:00:01,000 --> 00:00:02,000
+Hello there
+
+3
+00:00:02,000 --> 00:00:03,000
+After
+
+4
+00:00:03,000 --> 00:00:04,000
+Nothing
+
+5
+00:00:04,000 --> 00:00:05,000
+Far away
+
+6
+00:00:05,000 --> 00:00:06,000
+Hello again",
+        )
+        .unwrap();
+
+        let config = Config {
+            context: 1,
+            ..config("Hello")
+        };
+        let output = search(&config, &[&path]);
+
+        let expected = format!(
+            "{}\n{}  Before\n{}  {} there\n{}  After\n--\n{}  Far away\n{}  {} again\n",
+            Blue.paint("movie"),
+            Yellow.paint("00:00:00,000"),
+            Yellow.paint("00:00:01,000"),
+            Green.paint("Hello"),
+            Yellow.paint("00:00:02,000"),
+            Yellow.paint("00:00:04,000"),
+            Yellow.paint("00:00:05,000"),
+            Green.paint("Hello"),
+        );
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn extensions() {
+        let dir = temp_dir("extensions");
+        let subtitle = "\
+1
+00:00:00,000 --> 00:00:01,000
+Hello there";
+        fs::write(dir.join("a.srt"), subtitle).unwrap();
+        fs::write(dir.join("b.txt"), subtitle).unwrap();
+
+        let mut config = config("Hello");
+        let output = search(&config, &[&dir]);
+        assert!(output.contains(&Blue.paint("a").to_string()));
+        assert!(!output.contains(&Blue.paint("b").to_string()));
+
+        // files given explicitly are searched regardless
+        let output = search(&config, &[&dir.join("b.txt")]);
+        assert!(output.contains(&Blue.paint("b").to_string()));
+
+        config.extensions = vec![".TXT"];
+        let output = search(&config, &[&dir]);
+        assert!(!output.contains(&Blue.paint("a").to_string()));
+        assert!(output.contains(&Blue.paint("b").to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_loop() {
+        let dir = temp_dir("symlinks");
+        fs::create_dir(dir.join("sub")).unwrap();
+        fs::write(
+            dir.join("sub").join("a.srt"),
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+Hello there",
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(&dir, dir.join("sub").join("loop")).unwrap();
+
+        let mut config = config("Hello");
+        let stem = Blue.paint("a").to_string();
+
+        let output = search(&config, &[&dir]);
+        assert_eq!(1, output.matches(&stem).count());
+
+        config.follow = true;
+        let output = search(&config, &[&dir]);
+        assert_eq!(1, output.matches(&stem).count());
+    }
+
+    #[test]
+    fn json_with_context() {
+        let dir = temp_dir("json");
+        let path = dir.join("movie.srt");
+        fs::write(
+            &path,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+Before
+
+2
+00:00:01,000 --> 00:00:02,000
+Hello there
+Is anybody there?
+
+3
+00:00:02,000 --> 00:00:03,000
+After",
+        )
+        .unwrap();
+
+        let config = Config {
+            json: true,
+            context: 1,
+            ..config("there")
+        };
+        let output = search(&config, &[&path]);
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(2, lines.len());
+
+        let actual: Value = serde_json::from_str(lines[0]).unwrap();
+        let expected = json!({
+            "type": "match",
+            "path": path.to_string_lossy(),
+            "position": 2,
+            "start": "00:00:01,000",
+            "end": "00:00:02,000",
+            "line": 0,
+            "text": "Hello there",
+            "match": { "start": 6, "end": 11 },
+            "before": [{
+                "position": 1,
+                "start": "00:00:00,000",
+                "end": "00:00:01,000",
+                "text": ["Before"],
+            }],
+            "after": [{
+                "position": 3,
+                "start": "00:00:02,000",
+                "end": "00:00:03,000",
+                "text": ["After"],
+            }],
+        });
+        assert_eq!(expected, actual);
+
+        let actual: Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(1, actual["line"]);
+        assert_eq!("Is anybody there?", actual["text"]);
+        assert_eq!(json!({ "start": 11, "end": 16 }), actual["match"]);
+    }
+
+    #[test]
+    fn strip_tags() {
+        let dir = temp_dir("strip-tags");
+        let path = dir.join("movie.srt");
+        fs::write(
+            &path,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+<i>hello</i> there",
+        )
+        .unwrap();
+
+        let mut config = config("^hello");
+        let output = search(&config, &[&path]);
+        assert_eq!(format!("{}\n", Blue.paint("movie")), output);
+
+        config.strip_tags = true;
+        let output = search(&config, &[&path]);
+        let expected = format!(
+            "{}\n{}  {}{}\n",
+            Blue.paint("movie"),
+            Yellow.paint("00:00:00,000"),
+            Green.paint("hello"),
+            " there"
+        );
+        assert_eq!(expected, output);
+    }
+
+    #[test]
+    fn match_filename() {
+        let dir = temp_dir("match-filename");
+        let path = dir.join("The Pilot.srt");
+        fs::write(
+            &path,
+            "\
+1
+00:00:00,000 --> 00:00:01,000
+Hello there",
+        )
+        .unwrap();
+
+        let mut config = config("Pilot");
+        let output = search(&config, &[&path]);
+        assert_eq!(format!("{}\n", Blue.paint("The Pilot")), output);
+
+        config.match_filename = true;
+        let output = search(&config, &[&path]);
+        let expected = format!(
+            "{}{}{} (file name match)\n",
+            Blue.paint("The "),
+            Green.paint("Pilot"),
+            Blue.paint("")
+        );
+        assert_eq!(expected, output);
+
+        config.json = true;
+        let output = search(&config, &[&path]);
+        let actual: Value = serde_json::from_str(output.trim_end()).unwrap();
+        let expected = json!({
+            "type": "file_name",
+            "path": path.to_string_lossy(),
+            "file_name": "The Pilot",
+            "matches": [{ "start": 4, "end": 9 }],
+        });
+        assert_eq!(expected, actual);
+    }
+}
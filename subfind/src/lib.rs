@@ -0,0 +1,133 @@
+//! Find words in subtitles.
+//!
+//! This is the search engine of the `subfind` binary, which prints the
+//! matches found here.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! # use std::error::Error;
+//! use regex::Regex;
+//! use std::fs::File;
+//!
+//! let file = File::open("/path/to/subtitle.srt")?;
+//! let regex = Regex::new("Hello")?;
+//!
+//! for found in subfind::search(file, &regex) {
+//!     println!("{} {}", found.start, &found.text[found.range]);
+//! }
+//! # Ok::<(), Box<dyn Error>>(())
+//! ```
+
+use regex::Regex;
+use std::{io::Read, ops::Range};
+use subtitles::{SubRip, Timecode};
+
+/// A match of the pattern in a line of subtitle text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    /// Position of the subtitle.
+    pub position: usize,
+    /// The time that the subtitle should appear.
+    pub start: Timecode,
+    /// The time that the subtitle should disappear.
+    pub end: Timecode,
+    /// Index of the line in the subtitle text.
+    pub line: usize,
+    /// The whole line.
+    pub text: String,
+    /// Byte range of the match in `text`.
+    pub range: Range<usize>,
+}
+
+/// A match of the pattern in a line of subtitle text, borrowed from the
+/// subtitle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchRef<'a> {
+    /// The subtitle.
+    pub sub: &'a SubRip,
+    /// Index of the line in the subtitle text.
+    pub line: usize,
+    /// The whole line.
+    pub text: &'a str,
+    /// Byte range of the match in `text`.
+    pub range: Range<usize>,
+}
+
+impl MatchRef<'_> {
+    /// Copy this match out of the subtitle.
+    pub fn to_match(&self) -> Match {
+        Match {
+            position: self.sub.position,
+            start: self.sub.start.clone(),
+            end: self.sub.end.clone(),
+            line: self.line,
+            text: self.text.to_owned(),
+            range: self.range.clone(),
+        }
+    }
+}
+
+/// Find every match of `regex` in the SubRip (.srt) subtitles of `reader`,
+/// in order.
+///
+/// Subtitles that fail to parse are skipped.
+pub fn search<'a, T: Read + 'a>(reader: T, regex: &'a Regex) -> impl Iterator<Item = Match> + 'a {
+    subtitles::open(reader).valid_only().flat_map(move |sub| {
+        find_matches(regex, &sub)
+            .iter()
+            .map(MatchRef::to_match)
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Find every match of `regex` in `sub`, in order.
+pub fn find_matches<'a>(regex: &Regex, sub: &'a SubRip) -> Vec<MatchRef<'a>> {
+    let mut matches = Vec::new();
+    for (line, text) in sub.text.iter().enumerate() {
+        for found in regex.find_iter(text) {
+            matches.push(MatchRef {
+                sub,
+                line,
+                text,
+                range: found.range(),
+            });
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_in_memory() {
+        let subtitle = "\
+1
+00:00:01,000 --> 00:00:02,000
+Nothing here
+
+2
+00:00:03,000 --> 00:00:04,000
+Hello there
+Hello again, hello
+";
+        let regex = Regex::new("(?i)hello").unwrap();
+        let matches: Vec<Match> = search(subtitle.as_bytes(), &regex).collect();
+
+        assert_eq!(3, matches.len());
+        let first = &matches[0];
+        assert_eq!(2, first.position);
+        assert_eq!("00:00:03,000", first.start.to_string());
+        assert_eq!("00:00:04,000", first.end.to_string());
+        assert_eq!(0, first.line);
+        assert_eq!("Hello there", first.text);
+        assert_eq!(0..5, first.range);
+
+        let last = &matches[2];
+        assert_eq!(1, last.line);
+        assert_eq!(13..18, last.range);
+        assert_eq!("hello", &last.text[last.range.clone()]);
+    }
+}
@@ -1,13 +1,30 @@
-mod subfind;
+mod finder;
 mod options {
+    pub const COLOR: &str = "color";
+    pub const CONTEXT: &str = "context";
+    pub const EXT: &str = "ext";
+    pub const FILES_WITH_MATCHES: &str = "files-with-matches";
+    pub const FOLLOW: &str = "follow";
+    pub const INVERT: &str = "invert-match";
+    pub const JSON: &str = "json";
+    pub const MATCH_FILENAME: &str = "match-filename";
+    pub const MAX_COUNT: &str = "max-count";
+    pub const ONLY_MATCHING: &str = "only-matching";
     pub const PATH: &str = "path";
     pub const PATTERN: &str = "pattern";
+    pub const REPLACE: &str = "replace";
+    pub const STRIP_TAGS: &str = "strip-tags";
+    pub const UNIQUE: &str = "unique";
 }
 
 use clap::{App, Arg};
+use finder::Config;
 use regex::Regex;
-use std::{env, error::Error};
-use subfind::Config;
+use std::{
+    env,
+    error::Error,
+    io::{self, IsTerminal},
+};
 
 const NAME: &str = env!("CARGO_PKG_NAME");
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -34,12 +51,163 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .hide_default_value(true)
                 .multiple(true),
         )
+        .arg(
+            Arg::with_name(options::UNIQUE)
+                .long("unique")
+                .help("print each matching line only once across all files")
+                .conflicts_with_all(&[options::CONTEXT, options::JSON]),
+        )
+        .arg(
+            Arg::with_name(options::STRIP_TAGS)
+                .long("strip-tags")
+                .help("remove formatting tags before matching"),
+        )
+        .arg(
+            Arg::with_name(options::MATCH_FILENAME)
+                .long("match-filename")
+                .help("also search file names"),
+        )
+        .arg(
+            Arg::with_name(options::JSON)
+                .long("json")
+                .help("print one JSON object per match"),
+        )
+        .arg(
+            Arg::with_name(options::CONTEXT)
+                .short("C")
+                .long("context")
+                .value_name("N")
+                .help("print N subtitles before and after each match"),
+        )
+        .arg(
+            Arg::with_name(options::EXT)
+                .long("ext")
+                .value_name("EXT")
+                .help("extension of the files to search in directories")
+                .default_value("srt")
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name(options::FOLLOW)
+                .long("follow")
+                .help("follow symbolic links in directories"),
+        )
+        .arg(
+            Arg::with_name(options::ONLY_MATCHING)
+                .short("o")
+                .long("only-matching")
+                .help("print only the matched parts of lines, one per line")
+                .conflicts_with_all(&[options::JSON, options::CONTEXT]),
+        )
+        .arg(
+            Arg::with_name(options::REPLACE)
+                .long("replace")
+                .value_name("REPLACEMENT")
+                .help(
+                    "rewrite files in place, replacing matches with REPLACEMENT \
+                     (keeps line endings, encoding and permissions)",
+                )
+                .conflicts_with_all(&[
+                    options::CONTEXT,
+                    options::JSON,
+                    options::MATCH_FILENAME,
+                    options::ONLY_MATCHING,
+                    options::STRIP_TAGS,
+                    options::UNIQUE,
+                ]),
+        )
+        .arg(
+            Arg::with_name(options::COLOR)
+                .long("color")
+                .value_name("WHEN")
+                .help("when to color the output")
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto"),
+        )
+        .arg(
+            Arg::with_name(options::INVERT)
+                .short("v")
+                .long("invert-match")
+                .help("print the lines that don't match")
+                .conflicts_with_all(&[
+                    options::CONTEXT,
+                    options::JSON,
+                    options::ONLY_MATCHING,
+                    options::REPLACE,
+                ]),
+        )
+        .arg(
+            Arg::with_name(options::FILES_WITH_MATCHES)
+                .short("l")
+                .long("files-with-matches")
+                .help("print only the paths of the files with a match")
+                .conflicts_with_all(&[
+                    options::CONTEXT,
+                    options::JSON,
+                    options::ONLY_MATCHING,
+                    options::REPLACE,
+                    options::UNIQUE,
+                ]),
+        )
+        .arg(
+            Arg::with_name(options::MAX_COUNT)
+                .short("m")
+                .long("max-count")
+                .value_name("N")
+                .help("stop searching a file after N matching lines")
+                .conflicts_with_all(&[
+                    options::CONTEXT,
+                    options::FILES_WITH_MATCHES,
+                    options::JSON,
+                    options::REPLACE,
+                ]),
+        )
         .get_matches();
 
     let pattern = matches.value_of(options::PATTERN).unwrap();
     let regex = Regex::new(pattern)?;
     let paths = matches.values_of(options::PATH).unwrap().collect();
+    let unique = matches.is_present(options::UNIQUE);
+    let strip_tags = matches.is_present(options::STRIP_TAGS);
+    let match_filename = matches.is_present(options::MATCH_FILENAME);
+    let json = matches.is_present(options::JSON);
+    let extensions = matches.values_of(options::EXT).unwrap().collect();
+    let follow = matches.is_present(options::FOLLOW);
+    let only_matching = matches.is_present(options::ONLY_MATCHING);
+    let replace = matches.value_of(options::REPLACE);
+    let invert = matches.is_present(options::INVERT);
+    let files_with_matches = matches.is_present(options::FILES_WITH_MATCHES);
+    let color = match matches.value_of(options::COLOR) {
+        Some("always") => true,
+        Some("never") => false,
+        _ => io::stdout().is_terminal(),
+    };
+    let context = match matches.value_of(options::CONTEXT) {
+        Some(context) => context.parse()?,
+        None => 0,
+    };
+    let max_count = match matches.value_of(options::MAX_COUNT) {
+        Some(max_count) => Some(max_count.parse()?),
+        None => None,
+    };
 
-    let config = Config { regex, paths };
-    subfind::run(config)
+    let config = Config {
+        regex,
+        paths,
+        unique,
+        strip_tags,
+        match_filename,
+        json,
+        context,
+        extensions,
+        follow,
+        only_matching,
+        replace,
+        color,
+        invert,
+        files_with_matches,
+        max_count,
+    };
+    finder::run(config)
 }
@@ -0,0 +1,110 @@
+mod options {
+    pub const FILE: &str = "file";
+    pub const FIX_REVERSED: &str = "fix-reversed";
+    pub const IN_PLACE: &str = "in-place";
+    pub const MIN_GAP: &str = "min-gap";
+    pub const RENUMBER: &str = "renumber";
+    pub const TRIM: &str = "trim";
+}
+
+use clap::{App, Arg};
+use encoding_rs::UTF_8;
+use std::{
+    env,
+    error::Error,
+    fs,
+    io::{self, BufWriter},
+    time::Duration,
+};
+
+const NAME: &str = env!("CARGO_PKG_NAME");
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+const AUTHOR: &str = env!("CARGO_PKG_AUTHORS");
+const ABOUT: &str = env!("CARGO_PKG_DESCRIPTION");
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let matches = App::new(NAME)
+        .version(VERSION)
+        .author(AUTHOR)
+        .about(ABOUT)
+        .arg(
+            Arg::with_name(options::FILE)
+                .value_name("FILE")
+                .help("subtitle to fix")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(options::RENUMBER)
+                .long("renumber")
+                .help("number subtitles sequentially from 1"),
+        )
+        .arg(
+            Arg::with_name(options::FIX_REVERSED)
+                .long("fix-reversed")
+                .help("swap start and end of subtitles that end before they start"),
+        )
+        .arg(
+            Arg::with_name(options::MIN_GAP)
+                .long("min-gap")
+                .value_name("MS")
+                .help("keep at least MS milliseconds between subtitles"),
+        )
+        .arg(
+            Arg::with_name(options::TRIM)
+                .long("trim")
+                .help("trim whitespace and drop blank lines in text"),
+        )
+        .arg(
+            Arg::with_name(options::IN_PLACE)
+                .short("i")
+                .long("in-place")
+                .help("overwrite FILE instead of printing to standard output"),
+        )
+        .get_matches();
+
+    let path = matches.value_of(options::FILE).unwrap();
+
+    let in_place = matches.is_present(options::IN_PLACE);
+
+    let original = fs::read(path)?;
+    let mut parser = subtitles::open(&original[..]);
+    let mut subs = Vec::new();
+    let mut failed = false;
+    for entry in parser.by_ref() {
+        match entry {
+            Ok(sub) => subs.push(sub),
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                failed = true;
+            }
+        }
+    }
+    // rewriting the file would delete the subtitles that failed to parse
+    if in_place && failed {
+        return Err(format!("{} left unchanged because of the errors above", path).into());
+    }
+
+    for sub in &mut subs {
+        if matches.is_present(options::FIX_REVERSED) {
+            sub.fix_reversed();
+        }
+        if matches.is_present(options::TRIM) {
+            sub.trim_text();
+        }
+    }
+    if let Some(gap) = matches.value_of(options::MIN_GAP) {
+        subtitles::enforce_min_gap(&mut subs, Duration::from_millis(gap.parse()?));
+    }
+    if matches.is_present(options::RENUMBER) {
+        subtitles::renumber(&mut subs);
+    }
+
+    if in_place {
+        let encoding = parser.encoding().unwrap_or(UTF_8);
+        subtitles::rewrite_path(path, &original, encoding, subs)?;
+    } else {
+        subtitles::write(BufWriter::new(io::stdout()), subs)?;
+    }
+
+    Ok(())
+}
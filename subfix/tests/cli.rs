@@ -0,0 +1,101 @@
+use std::{env, fs, process::Command};
+
+#[test]
+fn fix_in_place() {
+    let dir = env::temp_dir().join(format!("subfix-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("movie.srt");
+    fs::write(
+        &path,
+        "\
+3
+00:00:01,000 --> 00:00:02,000
+  First  
+
+1
+00:00:04,000 --> 00:00:03,000
+Second
+",
+    )
+    .unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_subfix"))
+        .args(["--renumber", "--fix-reversed", "--trim", "-i"])
+        .arg(&path)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let expected = "\
+1
+00:00:01,000 --> 00:00:02,000
+First
+
+2
+00:00:03,000 --> 00:00:04,000
+Second
+
+";
+    assert_eq!(expected, fs::read_to_string(&path).unwrap());
+    assert_eq!(1, fs::read_dir(&dir).unwrap().count());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn corrupt_file_left_unchanged() {
+    let dir = env::temp_dir().join(format!("subfix-{}-corrupt", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("movie.srt");
+    let subtitle = "\
+2
+00:00:01,000 --> 00:00:02,000
+First
+
+1
+00:00:03,000 -> later
+Corrupt
+
+3
+00:00:05,000 --> 00:00:06,000
+Last
+";
+    fs::write(&path, subtitle).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_subfix"))
+        .args(["--renumber", "-i"])
+        .arg(&path)
+        .status()
+        .unwrap();
+    assert!(!status.success());
+
+    assert_eq!(subtitle.as_bytes(), &fs::read(&path).unwrap()[..]);
+    assert_eq!(1, fs::read_dir(&dir).unwrap().count());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn fix_in_place_keeps_format() {
+    let dir = env::temp_dir().join(format!("subfix-{}-format", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("movie.srt");
+    fs::write(
+        &path,
+        "\u{FEFF}3\r\n00:00:01,000 --> 00:00:02,000\r\nFirst\r\n",
+    )
+    .unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_subfix"))
+        .args(["--renumber", "-i"])
+        .arg(&path)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let expected = "\u{FEFF}1\r\n00:00:01,000 --> 00:00:02,000\r\nFirst\r\n\r\n";
+    assert_eq!(expected, fs::read_to_string(&path).unwrap());
+    assert_eq!(1, fs::read_dir(&dir).unwrap().count());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,46 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use std::fmt::Write;
+
+/// A SubRip file of `count` two-line subtitles.
+fn synthetic_file(count: usize) -> String {
+    let mut file = String::new();
+    for i in 0..count {
+        let start = i * 2000;
+        let end = start + 1500;
+        write!(
+            file,
+            "{}\n{:02}:{:02}:{:02},{:03} --> {:02}:{:02}:{:02},{:03}\n\
+             Subtitle number {}\nwith a second line of text\n\n",
+            i + 1,
+            start / 3_600_000,
+            start / 60_000 % 60,
+            start / 1000 % 60,
+            start % 1000,
+            end / 3_600_000,
+            end / 60_000 % 60,
+            end / 1000 % 60,
+            end % 1000,
+            i + 1
+        )
+        .unwrap();
+    }
+    file
+}
+
+fn parse(c: &mut Criterion) {
+    let file = synthetic_file(10_000);
+
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Bytes(file.len() as u64));
+    group.bench_function("srt", |b| {
+        b.iter(|| {
+            subtitles::open(black_box(file.as_bytes()))
+                .map(Result::unwrap)
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
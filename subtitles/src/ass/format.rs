@@ -0,0 +1,24 @@
+use crate::subrip::format::Timecode;
+
+/// A `Dialogue` event of an ASS/SSA (.ass, .ssa) file
+#[derive(Debug, PartialEq)]
+pub struct Event {
+    /// The time that the event should appear.
+    pub start: Timecode,
+    /// The time that the event should disappear.
+    pub end: Timecode,
+    /// Name of the style used by this event.
+    pub style: String,
+    /// The raw text, override tags such as `{\i1}` included.
+    pub text: String,
+}
+
+/// A style defined in the `[V4+ Styles]` section
+#[derive(Debug, PartialEq)]
+pub struct Style {
+    /// Style name
+    pub name: String,
+    /// Every field of the style as name and value pairs, as named by the
+    /// `Format` line of the section.
+    pub fields: Vec<(String, String)>,
+}
@@ -0,0 +1,34 @@
+//! ASS/SSA (.ass, .ssa) subtitles.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! # use std::io::Error;
+//! use std::fs::File;
+//!
+//! let file = File::open("/path/to/subtitle.ass")?;
+//!
+//! for event in subtitles::ass::open(file) {
+//!     match event {
+//!         Ok(event) => println!("{} {}", event.start, event.text),
+//!         Err(err) => eprintln!("{}", err),
+//!     }
+//! }
+//! # Ok::<(), Error>(())
+//! ```
+
+mod format;
+mod parser;
+
+use std::io::Read;
+
+pub use format::{Event, Style};
+pub use parser::AssParser;
+
+/// Create a new parser for `subtitle`.
+///
+/// `subtitle` must be in ASS or SSA format. Only `Dialogue` events are
+/// yielded, `[Script Info]` and the styles are available from the parser.
+pub fn open<T: Read>(subtitle: T) -> AssParser<T> {
+    AssParser::from(subtitle)
+}
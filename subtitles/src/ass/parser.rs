@@ -0,0 +1,300 @@
+use super::format::{Event, Style};
+use crate::{
+    error::{Error, ErrorKind},
+    lines::Lines,
+    subrip::{core::digits, format::Timecode},
+};
+use std::{error, io::Read, result};
+
+type Result<T> = result::Result<T, Box<dyn error::Error>>;
+type ParseResult<T> = result::Result<T, Error>;
+
+const DEFAULT_EVENT_FORMAT: &str =
+    "Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    ScriptInfo,
+    Styles,
+    Events,
+    Other,
+}
+
+/// An iterator over the dialogue events of an ASS/SSA (.ass, .ssa) file.
+///
+/// This `struct` is created by [`open`](super::open).
+pub struct AssParser<T: Read> {
+    subtitle: Lines<T>,
+    section: Section,
+    script_info: Vec<(String, String)>,
+    style_format: Vec<String>,
+    styles: Vec<Style>,
+    event_format: Vec<String>,
+}
+
+impl<T: Read> AssParser<T> {
+    /// The `key: value` pairs of the `[Script Info]` section read so far.
+    pub fn script_info(&self) -> &[(String, String)] {
+        &self.script_info
+    }
+
+    /// The styles read so far.
+    pub fn styles(&self) -> &[Style] {
+        &self.styles
+    }
+
+    fn parse_next(&mut self) -> ParseResult<Option<Event>> {
+        loop {
+            let line = match self.subtitle.next_line() {
+                Ok(Some(line)) => line,
+                Ok(None) => return Ok(None),
+                Err(err) => return Err(Error::new(ErrorKind::Io, err)),
+            };
+            let line = line.trim();
+
+            if line.starts_with('[') && line.ends_with(']') {
+                self.section = match line.to_lowercase().as_str() {
+                    "[script info]" => Section::ScriptInfo,
+                    "[v4+ styles]" | "[v4 styles]" => Section::Styles,
+                    "[events]" => Section::Events,
+                    _ => Section::Other,
+                };
+                continue;
+            }
+
+            // comments start with `;`
+            let (key, value) = match line.find(':') {
+                Some(index) if !line.starts_with(';') => {
+                    (line[..index].trim(), line[index + 1..].trim())
+                }
+                _ => continue,
+            };
+
+            match (self.section, key) {
+                (Section::ScriptInfo, _) => {
+                    self.script_info.push((key.to_owned(), value.to_owned()));
+                }
+                (Section::Styles, "Format") => self.style_format = parse_format(value),
+                (Section::Styles, "Style") => {
+                    let fields = split_fields(value, self.style_format.len());
+                    let fields: Vec<(String, String)> = self
+                        .style_format
+                        .iter()
+                        .cloned()
+                        .zip(fields.into_iter().map(String::from))
+                        .collect();
+                    let name = field(&fields, "Name").unwrap_or_default().to_owned();
+                    self.styles.push(Style { name, fields });
+                }
+                (Section::Events, "Format") => self.event_format = parse_format(value),
+                (Section::Events, "Dialogue") => {
+                    return parse_dialogue(value, &self.event_format)
+                        .map(Some)
+                        .map_err(|err| Error::new(ErrorKind::InvalidEvent, err));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn parse_format(format: &str) -> Vec<String> {
+    format
+        .split(',')
+        .map(|name| name.trim().to_owned())
+        .collect()
+}
+
+/// Split `value` into `count` fields, the last one keeping any commas.
+fn split_fields(value: &str, count: usize) -> Vec<&str> {
+    value.splitn(count, ',').map(str::trim).collect()
+}
+
+fn field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(field, _)| field.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+fn parse_dialogue(value: &str, format: &[String]) -> Result<Event> {
+    let values = value.splitn(format.len(), ',');
+    if values.clone().count() != format.len() {
+        return Err(format!("expected {} fields", format.len()).into());
+    }
+
+    let fields: Vec<(String, String)> = format
+        .iter()
+        .cloned()
+        .zip(values.map(String::from))
+        .collect();
+    let get = |name| field(&fields, name).ok_or(format!("missing `{}` field", name));
+
+    Ok(Event {
+        start: parse_time(get("Start")?.trim())?,
+        end: parse_time(get("End")?.trim())?,
+        style: get("Style")?.trim().to_owned(),
+        text: get("Text")?.to_owned(),
+    })
+}
+
+/// Parse a time in the `H:MM:SS.cc` form.
+///
+/// The fraction may also be a single digit, in tenths of a second.
+/// Minutes and seconds must be below 60.
+fn parse_time(time: &str) -> Result<Timecode> {
+    let err = "wrong time format";
+
+    let fields: Vec<&str> = time.split(&[':', '.'][..]).collect();
+    let (hours, minutes, seconds, fraction) = match fields[..] {
+        [hours, minutes, seconds, fraction] => (hours, minutes, seconds, fraction),
+        _ => return Err(err.into()),
+    };
+    let scale = match fraction.len() {
+        1 => 100,
+        2 => 10,
+        _ => return Err(err.into()),
+    };
+    let fraction: u16 = digits(fraction, None).ok_or(err)?.parse()?;
+
+    let timecode = Timecode {
+        hours: digits(hours, None).ok_or(err)?.parse()?,
+        minutes: digits(minutes, Some(2)).ok_or(err)?.parse()?,
+        seconds: digits(seconds, Some(2)).ok_or(err)?.parse()?,
+        milliseconds: fraction * scale,
+    };
+    if timecode.minutes > 59 || timecode.seconds > 59 {
+        return Err("minutes and seconds must be below 60".into());
+    }
+
+    Ok(timecode)
+}
+
+impl<T: Read> From<T> for AssParser<T> {
+    fn from(subtitle: T) -> Self {
+        AssParser {
+            subtitle: Lines::new(subtitle),
+            section: Section::Other,
+            script_info: Vec::new(),
+            style_format: Vec::new(),
+            styles: Vec::new(),
+            event_format: parse_format(DEFAULT_EVENT_FORMAT),
+        }
+    }
+}
+
+impl<T: Read> Iterator for AssParser<T> {
+    type Item = ParseResult<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parse_next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUBTITLE: &str = "\
+[Script Info]
+; comment
+Title: Example
+ScriptType: v4.00+
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize
+Style: Default,Arial,20
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.50,0:00:03.00,Default,,0,0,0,,{\\i1}Hello{\\i0}, world
+Dialogue: broken
+Dialogue: 0,1:02:03.04,1:02:05.99,Default,,0,0,0,,Second\\Nline";
+
+    #[test]
+    fn parse_events() {
+        let mut parser = AssParser::from(SUBTITLE.as_bytes());
+
+        let expected = Event {
+            start: Timecode {
+                hours: 0,
+                minutes: 0,
+                seconds: 1,
+                milliseconds: 500,
+            },
+            end: Timecode {
+                hours: 0,
+                minutes: 0,
+                seconds: 3,
+                milliseconds: 0,
+            },
+            style: String::from("Default"),
+            text: String::from("{\\i1}Hello{\\i0}, world"),
+        };
+        assert_eq!(expected, parser.next().unwrap().unwrap());
+
+        let err = parser.next().unwrap().unwrap_err();
+        assert_eq!(ErrorKind::InvalidEvent, err.kind());
+
+        let expected = Event {
+            start: Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                milliseconds: 40,
+            },
+            end: Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 5,
+                milliseconds: 990,
+            },
+            style: String::from("Default"),
+            text: String::from("Second\\Nline"),
+        };
+        assert_eq!(expected, parser.next().unwrap().unwrap());
+
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn parse_headers() {
+        let mut parser = AssParser::from(SUBTITLE.as_bytes());
+        parser.by_ref().for_each(drop);
+
+        assert_eq!(
+            vec![
+                (String::from("Title"), String::from("Example")),
+                (String::from("ScriptType"), String::from("v4.00+")),
+            ],
+            parser.script_info()
+        );
+
+        let expected = vec![Style {
+            name: String::from("Default"),
+            fields: vec![
+                (String::from("Name"), String::from("Default")),
+                (String::from("Fontname"), String::from("Arial")),
+                (String::from("Fontsize"), String::from("20")),
+            ],
+        }];
+        assert_eq!(expected, parser.styles());
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(
+            "01:02:03,450",
+            parse_time("1:02:03.45").unwrap().to_string()
+        );
+        assert_eq!("00:00:01,500", parse_time("0:00:01.5").unwrap().to_string());
+
+        assert!(parse_time("0:00:01.999").is_err());
+        assert!(parse_time("0:00:01.9999").is_err());
+        assert!(parse_time("0:00:01.").is_err());
+        assert!(parse_time("0:00:01.+5").is_err());
+        assert!(parse_time("0:75:99.00").is_err());
+        assert!(parse_time("0:00:60.00").is_err());
+        assert!(parse_time("0:1:02.00").is_err());
+    }
+}
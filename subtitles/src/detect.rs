@@ -0,0 +1,204 @@
+use crate::sbv;
+use encoding_rs::{Encoding, UTF_8};
+use std::io::{self, BufRead};
+
+/// A subtitle format recognized by [`detect`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// SubRip (.srt), see [`open`](crate::open).
+    SubRip,
+    /// WebVTT (.vtt), see [`webvtt`](crate::webvtt).
+    WebVtt,
+    /// ASS/SSA (.ass, .ssa), see [`ass`](crate::ass).
+    Ass,
+    /// MicroDVD (.sub), see [`microdvd`](crate::microdvd).
+    MicroDvd,
+    /// SubViewer (.sbv), see [`sbv`](crate::sbv).
+    Sbv,
+    /// LRC lyrics (.lrc), see [`lrc`](crate::lrc).
+    Lrc,
+    /// SAMI (.smi), see [`sami`](crate::sami).
+    Sami,
+    /// TTML (.ttml, .dfxp), see [`ttml`](crate::ttml).
+    Ttml,
+    /// MPL2, see [`mpl2`](crate::mpl2).
+    Mpl2,
+}
+
+/// Guess the format of `subtitle` from its first lines.
+///
+/// Only the bytes already buffered by `subtitle` are inspected, and none
+/// of them are consumed, so `subtitle` can be handed to the parser of the
+/// detected format afterwards. Returns `None` if the format is not
+/// recognized.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::io::Error;
+/// use std::{fs::File, io::BufReader};
+/// use subtitles::Format;
+///
+/// let mut file = BufReader::new(File::open("/path/to/subtitle")?);
+/// if subtitles::detect(&mut file)? == Some(Format::WebVtt) {
+///     for cue in subtitles::webvtt::open(file) {
+///         println!("{:?}", cue);
+///     }
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub fn detect<T: BufRead>(subtitle: &mut T) -> io::Result<Option<Format>> {
+    let buf = subtitle.fill_buf()?;
+    let (encoding, bom_length) = Encoding::for_bom(buf).unwrap_or((UTF_8, 0));
+    let (text, _) = encoding.decode_without_bom_handling(&buf[bom_length..]);
+
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let first = match lines.next() {
+        Some(line) => line,
+        None => return Ok(None),
+    };
+
+    let format = if first.starts_with("WEBVTT") {
+        Some(Format::WebVtt)
+    } else if first.eq_ignore_ascii_case("[Script Info]") {
+        Some(Format::Ass)
+    } else if first
+        .get(..6)
+        .is_some_and(|tag| tag.eq_ignore_ascii_case("<SAMI>"))
+    {
+        Some(Format::Sami)
+    } else if first.starts_with('<') && is_ttml(&text) {
+        Some(Format::Ttml)
+    } else if is_lrc(first) {
+        Some(Format::Lrc)
+    } else if starts_with_times(first, '{', '}') {
+        Some(Format::MicroDvd)
+    } else if starts_with_times(first, '[', ']') {
+        Some(Format::Mpl2)
+    } else if sbv::parse_timing(first).is_ok() {
+        Some(Format::Sbv)
+    } else if first.parse::<usize>().is_ok()
+        && lines.next().is_some_and(|line| line.contains("-->"))
+    {
+        Some(Format::SubRip)
+    } else {
+        None
+    };
+    Ok(format)
+}
+
+/// Whether `line` starts with a time or metadata tag, as in `[00:12.00]`
+/// or `[ti:Title]`.
+fn is_lrc(line: &str) -> bool {
+    line.strip_prefix('[')
+        .and_then(|line| line.split(']').next())
+        .is_some_and(|tag| tag.contains(':'))
+}
+
+/// Whether `text` holds a `<tt>` root element, possibly with a prefix as in
+/// `<tt:tt>`.
+fn is_ttml(text: &str) -> bool {
+    text.match_indices("<tt").any(|(index, _)| {
+        let name = &text[index + 1..];
+        let name = &name[..name
+            .find(|c: char| c.is_whitespace() || c == '>')
+            .unwrap_or(name.len())];
+        name == "tt" || name.ends_with(":tt")
+    })
+}
+
+/// Whether `line` starts with two numbers between `open` and `close`, as
+/// in MicroDVD's `{1}{25}` or MPL2's `[1][25]`.
+fn starts_with_times(line: &str, open: char, close: char) -> bool {
+    let time = |line: &str| -> Option<usize> {
+        let line = line.strip_prefix(open)?;
+        let digits = line.find(close)?;
+        if digits > 0 && line[..digits].bytes().all(|b| b.is_ascii_digit()) {
+            Some(digits + 2)
+        } else {
+            None
+        }
+    };
+
+    match time(line) {
+        Some(length) => time(&line[length..]).is_some(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn detect_str(subtitle: &str) -> Option<Format> {
+        detect(&mut subtitle.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn webvtt() {
+        let mut subtitle = "WEBVTT\n\n00:01.000 --> 00:02.000\nHello\n".as_bytes();
+
+        assert_eq!(Some(Format::WebVtt), detect(&mut subtitle).unwrap());
+
+        let mut rest = String::new();
+        subtitle.read_to_string(&mut rest).unwrap();
+        assert!(rest.starts_with("WEBVTT"));
+    }
+
+    #[test]
+    fn subrip() {
+        let subtitle = "\u{FEFF}1\n00:00:01,000 --> 00:00:02,000\nHello\n";
+        assert_eq!(Some(Format::SubRip), detect_str(subtitle));
+    }
+
+    #[test]
+    fn ass() {
+        let subtitle = "[Script Info]\nTitle: Test\n";
+        assert_eq!(Some(Format::Ass), detect_str(subtitle));
+    }
+
+    #[test]
+    fn microdvd() {
+        assert_eq!(Some(Format::MicroDvd), detect_str("{1}{25}Hello|World\n"));
+    }
+
+    #[test]
+    fn mpl2() {
+        assert_eq!(Some(Format::Mpl2), detect_str("[123][456]Hello|World\n"));
+    }
+
+    #[test]
+    fn sbv() {
+        let subtitle = "0:00:01.000,0:00:02.500\nHello\n";
+        assert_eq!(Some(Format::Sbv), detect_str(subtitle));
+    }
+
+    #[test]
+    fn lrc() {
+        assert_eq!(Some(Format::Lrc), detect_str("[ti:Song]\n[00:12.00]Line\n"));
+        assert_eq!(Some(Format::Lrc), detect_str("[00:12.00]Line\n"));
+    }
+
+    #[test]
+    fn sami() {
+        let subtitle = "<SAMI>\n<BODY>\n<SYNC Start=1000><P>Hello\n";
+        assert_eq!(Some(Format::Sami), detect_str(subtitle));
+        assert_eq!(Some(Format::Sami), detect_str("<sami><body>\n"));
+    }
+
+    #[test]
+    fn ttml() {
+        let subtitle = "<?xml version=\"1.0\"?>\n<tt xmlns=\"http://www.w3.org/ns/ttml\">\n";
+        assert_eq!(Some(Format::Ttml), detect_str(subtitle));
+        assert_eq!(Some(Format::Ttml), detect_str("<tt:tt xmlns:tt=\"\">\n"));
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(None, detect_str(""));
+        assert_eq!(None, detect_str("Hello\nWorld\n"));
+        assert_eq!(None, detect_str("1\nHello\n"));
+        assert_eq!(None, detect_str("{1}{}Hello\n"));
+    }
+}
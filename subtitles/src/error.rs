@@ -0,0 +1,109 @@
+use std::{error, fmt};
+
+/// An error that occurred while parsing a subtitle.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    error: Box<dyn error::Error>,
+    line: Option<usize>,
+    byte_offset: Option<u64>,
+}
+
+/// The kind of an [`Error`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorKind {
+    /// The position line is malformed.
+    InvalidPosition,
+    /// The timecode line is malformed.
+    InvalidTimecode,
+    /// The text is malformed.
+    InvalidText,
+    /// A subtitle starts before the one preceding it.
+    OutOfOrder,
+    /// An ASS/SSA event is malformed.
+    InvalidEvent,
+    /// The input couldn't be read.
+    Io,
+}
+
+impl ErrorKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::InvalidPosition => "invalid position",
+            ErrorKind::InvalidTimecode => "invalid timecode",
+            ErrorKind::InvalidText => "invalid text",
+            ErrorKind::OutOfOrder => "out of order",
+            ErrorKind::InvalidEvent => "invalid event",
+            ErrorKind::Io => "io error",
+        }
+    }
+}
+
+impl Error {
+    /// Create a new error of the given `kind`.
+    pub fn new<E>(kind: ErrorKind, error: E) -> Error
+    where
+        E: Into<Box<dyn error::Error>>,
+    {
+        Error {
+            kind,
+            error: error.into(),
+            line: None,
+            byte_offset: None,
+        }
+    }
+
+    pub(crate) fn at(mut self, line: usize, byte_offset: u64) -> Error {
+        self.line = Some(line);
+        self.byte_offset = Some(byte_offset);
+        self
+    }
+
+    /// The kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The line of the input, starting at 1, where this error occurred.
+    ///
+    /// Only known for errors returned by a parser.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// The offset in bytes from the start of the input, including any BOM,
+    /// of the line where this error occurred.
+    ///
+    /// Known whenever [`line`](Error::line) is.
+    pub fn byte_offset(&self) -> Option<u64> {
+        self.byte_offset
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(fmt, "line {}: ", line)?;
+        }
+        write!(fmt, "{}: {}", self.kind.as_str(), self.error)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// A problem that doesn't prevent a subtitle from being parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Warning {
+    /// A subtitle is not separated from the previous one by exactly one
+    /// blank line.
+    Separator {
+        /// Position of the subtitle after the separator.
+        position: usize,
+        /// Number of blank lines in the separator.
+        blank_lines: usize,
+    },
+}
@@ -6,10 +6,7 @@
 //!
 //! ```no_run
 //! # use std::io::Error;
-//! use std::fs::File;
-//!
-//! let file = File::open("/path/to/subtitle.srt")?;
-//! let parser = subtitles::open(file);
+//! let parser = subtitles::open_path("/path/to/subtitle.srt")?;
 //!
 //! for subtitle in parser {
 //!     match subtitle {
@@ -20,7 +17,27 @@
 //! # Ok::<(), Error>(())
 //! ```
 
+pub mod ass;
+mod detect;
+mod error;
+mod lines;
+pub mod lrc;
+pub mod microdvd;
+pub mod mpl2;
+pub mod sami;
+pub mod sbv;
 mod subrip;
+pub mod ttml;
+pub mod webvtt;
 
-pub use subrip::format::SubRip;
-pub use subrip::open;
+pub use detect::{detect, Format};
+pub use error::{Error, ErrorKind, Warning};
+pub use subrip::format::{Precision, SubRip, Timecode};
+#[cfg(feature = "flate2")]
+pub use subrip::open_auto;
+pub use subrip::{
+    cue_at, cues_at, decode_entities, duration_histogram, enforce_min_gap, find_overlaps,
+    flag_durations, join_sentences, map_cues, merge_overlapping, open, open_path, parse_all,
+    parse_str, renumber, require_monotonic, rewrite_path, strip_tags, to_lrc, to_string, to_webvtt,
+    write, FailFast, MapCues, RequireMonotonic, SkipEmpty, SubRipParser, ValidOnly, Writer,
+};
@@ -0,0 +1,58 @@
+use crate::subrip::core::trim_newline;
+use std::io::{self, BufRead, BufReader, Read};
+
+/// Reads lines of UTF-8 text, dropping a leading BOM and line endings.
+///
+/// Malformed UTF-8 is replaced with U+FFFD.
+pub(crate) struct Lines<T: Read> {
+    reader: BufReader<T>,
+    first_line: bool,
+}
+
+impl<T: Read> Lines<T> {
+    pub fn new(reader: T) -> Self {
+        Lines {
+            reader: BufReader::new(reader),
+            first_line: true,
+        }
+    }
+
+    pub fn next_line(&mut self) -> io::Result<Option<String>> {
+        let mut buf = Vec::new();
+        self.reader.read_until(b'\n', &mut buf)?;
+
+        if buf.is_empty() {
+            return Ok(None);
+        }
+
+        let mut line = String::from_utf8_lossy(&buf).into_owned();
+        if self.first_line {
+            self.first_line = false;
+            if line.starts_with('\u{FEFF}') {
+                line.remove(0);
+            }
+        }
+        trim_newline(&mut line);
+
+        Ok(Some(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        let text = "\u{FEFF}first\r\nsecond\n\u{FEFF}third";
+        let mut lines = Lines::new(text.as_bytes());
+
+        assert_eq!(Some(String::from("first")), lines.next_line().unwrap());
+        assert_eq!(Some(String::from("second")), lines.next_line().unwrap());
+        assert_eq!(
+            Some(String::from("\u{FEFF}third")),
+            lines.next_line().unwrap()
+        );
+        assert_eq!(None, lines.next_line().unwrap());
+    }
+}
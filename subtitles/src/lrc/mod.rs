@@ -0,0 +1,36 @@
+//! LRC (.lrc) lyrics.
+//!
+//! Each lyric line is prefixed by one or more `[mm:ss.xx]` time tags.
+//! Lines are parsed into [`SubRip`](crate::SubRip) subtitles, each one
+//! displayed until the next one starts. Metadata tags such as `[ti:Title]`
+//! are available through [`LrcParser::metadata`]. Use
+//! [`to_lrc`](crate::to_lrc) to write subtitles as LRC.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! # use std::io::Error;
+//! use std::fs::File;
+//!
+//! let file = File::open("/path/to/lyrics.lrc")?;
+//! for subtitle in subtitles::lrc::open(file) {
+//!     match subtitle {
+//!         Ok(sub) => println!("{}", sub),
+//!         Err(err) => eprintln!("{}", err),
+//!     }
+//! }
+//! # Ok::<(), Error>(())
+//! ```
+
+mod parser;
+
+use std::io::Read;
+
+pub use parser::{LrcParser, LAST_LINE_DURATION};
+
+/// Create a new parser for `subtitle`.
+///
+/// `subtitle` must be in LRC (.lrc) format.
+pub fn open<T: Read>(subtitle: T) -> LrcParser<T> {
+    LrcParser::from(subtitle)
+}
@@ -0,0 +1,271 @@
+use crate::{
+    error::{Error, ErrorKind},
+    lines::Lines,
+    subrip::format::{SubRip, Timecode},
+};
+use std::{collections::VecDeque, error, io::Read, result, time::Duration};
+
+type Result<T> = result::Result<T, Box<dyn error::Error>>;
+type ParseResult<T> = result::Result<T, Error>;
+
+/// How long the last lyric line is displayed, as nothing follows it.
+pub const LAST_LINE_DURATION: Duration = Duration::from_secs(5);
+
+/// An iterator over the lyric lines of an LRC (.lrc) file.
+///
+/// A line with several time tags is repeated at each of them, so the
+/// whole file is read on the first call to `next` and the lines are sorted
+/// by time. Errors are yielded first, in file order.
+///
+/// Each line is displayed until the next one starts. A time tag without
+/// text only ends the line before it. Lines are numbered sequentially
+/// from 1.
+///
+/// This `struct` is created by [`open`](super::open).
+pub struct LrcParser<T: Read> {
+    subtitle: Lines<T>,
+    metadata: Vec<(String, String)>,
+    read: bool,
+    errors: VecDeque<Error>,
+    lines: VecDeque<(Duration, String)>,
+    position: usize,
+}
+
+impl<T: Read> LrcParser<T> {
+    /// The `[key:value]` metadata tags read so far, such as `ti` for the
+    /// title or `ar` for the artist.
+    ///
+    /// An `offset` tag is not applied to the timings.
+    pub fn metadata(&self) -> &[(String, String)] {
+        &self.metadata
+    }
+
+    fn parse_next(&mut self) -> ParseResult<Option<SubRip>> {
+        if !self.read {
+            self.read_all();
+        }
+        if let Some(err) = self.errors.pop_front() {
+            return Err(err);
+        }
+
+        loop {
+            let (start, text) = match self.lines.pop_front() {
+                Some(line) => line,
+                None => return Ok(None),
+            };
+            if text.is_empty() {
+                continue;
+            }
+            let end = match self.lines.front() {
+                Some((next, _)) => *next,
+                None => start
+                    .checked_add(LAST_LINE_DURATION)
+                    .unwrap_or(Duration::MAX),
+            };
+
+            self.position += 1;
+            return Ok(Some(SubRip {
+                position: self.position,
+                start: Timecode::from_duration(start),
+                end: Timecode::from_duration(end),
+                text: vec![text],
+            }));
+        }
+    }
+
+    fn read_all(&mut self) {
+        self.read = true;
+        loop {
+            match self.subtitle.next_line() {
+                Ok(Some(line)) => {
+                    if let Err(err) = self.parse_line(line.trim()) {
+                        self.errors
+                            .push_back(Error::new(ErrorKind::InvalidTimecode, err));
+                    }
+                }
+                Ok(None) => break,
+                Err(err) => {
+                    self.errors.push_back(Error::new(ErrorKind::Io, err));
+                    break;
+                }
+            }
+        }
+        self.lines.make_contiguous().sort_by_key(|(time, _)| *time);
+    }
+
+    /// Record the lyrics or the metadata of `line`.
+    fn parse_line(&mut self, line: &str) -> Result<()> {
+        if line.is_empty() {
+            return Ok(());
+        }
+
+        let mut times = Vec::new();
+        let mut rest = line;
+        while let Some(tag) = rest.strip_prefix('[') {
+            let close = tag
+                .find(']')
+                .ok_or_else(|| format!("unclosed tag in `{}`", line))?;
+            let (tag, after) = (&tag[..close], &tag[close + 1..]);
+
+            if tag.starts_with(|c: char| c.is_ascii_digit()) {
+                times.push(parse_time(tag)?);
+                rest = after;
+            } else {
+                let (key, value) = tag.split_at(tag.find(':').unwrap_or(tag.len()));
+                let value = value.strip_prefix(':').unwrap_or(value);
+                self.metadata
+                    .push((key.trim().to_string(), value.trim().to_string()));
+                return Ok(());
+            }
+        }
+
+        if times.is_empty() {
+            return Err(format!("expected `[mm:ss.xx]`, found `{}`", line).into());
+        }
+        for time in times {
+            self.lines.push_back((time, rest.trim().to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Parse a time tag in the `mm:ss.xx` form.
+///
+/// Minutes may exceed 59, and the fraction may have any number of digits.
+fn parse_time(time: &str) -> Result<Duration> {
+    let err = || format!("wrong time format `{}`", time);
+
+    let colon = time.find(':').ok_or_else(err)?;
+    let minutes: u64 = time[..colon].parse()?;
+    let seconds = &time[colon + 1..];
+
+    let (seconds, fraction) = match seconds.find('.') {
+        Some(dot) => (&seconds[..dot], &seconds[dot + 1..]),
+        None => (seconds, ""),
+    };
+    let seconds: u64 = seconds.parse()?;
+    if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(err().into());
+    }
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        // digits past nanoseconds are truncated
+        let digits = format!("{:0<9}", &fraction[..fraction.len().min(9)]);
+        digits.parse()?
+    };
+
+    let seconds = minutes
+        .checked_mul(60)
+        .and_then(|minutes| minutes.checked_add(seconds))
+        .ok_or_else(err)?;
+    Ok(Duration::new(seconds, nanos))
+}
+
+impl<T: Read> From<T> for LrcParser<T> {
+    fn from(subtitle: T) -> Self {
+        LrcParser {
+            subtitle: Lines::new(subtitle),
+            metadata: Vec::new(),
+            read: false,
+            errors: VecDeque::new(),
+            lines: VecDeque::new(),
+            position: 0,
+        }
+    }
+}
+
+impl<T: Read> Iterator for LrcParser<T> {
+    type Item = ParseResult<SubRip>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parse_next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lyrics() {
+        let lyrics = "\
+[ti:Song]
+[ar: Artist]
+
+[00:12.00]First line
+[00:17.20]Second line
+[61:02.5]Third line
+";
+        let mut parser = LrcParser::from(lyrics.as_bytes());
+        let subs: Vec<SubRip> = parser.by_ref().map(|sub| sub.unwrap()).collect();
+
+        assert_eq!(3, subs.len());
+        assert_eq!(vec!["First line"], subs[0].text);
+        assert_eq!("00:00:12,000", subs[0].start.to_string());
+        assert_eq!("00:00:17,200", subs[0].end.to_string());
+        assert_eq!("00:00:17,200", subs[1].start.to_string());
+        assert_eq!("01:01:02,500", subs[1].end.to_string());
+        assert_eq!(3, subs[2].position);
+        assert_eq!("01:01:02,500", subs[2].start.to_string());
+        assert_eq!("01:01:07,500", subs[2].end.to_string());
+
+        let metadata = [
+            (String::from("ti"), String::from("Song")),
+            (String::from("ar"), String::from("Artist")),
+        ];
+        assert_eq!(&metadata[..], parser.metadata());
+    }
+
+    #[test]
+    fn repeated_and_empty_lines() {
+        let lyrics = "\
+[00:01.00][00:05.00]Chorus
+[00:03.00]
+";
+        let subs: Vec<SubRip> = LrcParser::from(lyrics.as_bytes())
+            .map(|sub| sub.unwrap())
+            .collect();
+
+        assert_eq!(2, subs.len());
+        assert_eq!("00:00:01,000", subs[0].start.to_string());
+        assert_eq!("00:00:03,000", subs[0].end.to_string());
+        assert_eq!(2, subs[1].position);
+        assert_eq!("00:00:05,000", subs[1].start.to_string());
+        assert_eq!("00:00:10,000", subs[1].end.to_string());
+    }
+
+    #[test]
+    fn invalid_line() {
+        let lyrics = "\
+[00:1x.00]Broken
+No tag
+[00:03.00]Fine";
+        let mut parser = LrcParser::from(lyrics.as_bytes());
+
+        assert!(parser.next().unwrap().is_err());
+        assert!(parser.next().unwrap().is_err());
+        assert_eq!(vec!["Fine"], parser.next().unwrap().unwrap().text);
+    }
+
+    #[test]
+    fn malformed_times() {
+        assert!(parse_time("00:01.ééééé").is_err());
+        assert!(parse_time("18446744073709551615:00.00").is_err());
+        assert_eq!(
+            Duration::new(61, 123_456_789),
+            parse_time("01:01.1234567891").unwrap()
+        );
+
+        let mut parser = LrcParser::from("[00:01.ééééé]x\n[00:02.00]Fine".as_bytes());
+        assert!(parser.next().unwrap().is_err());
+        assert_eq!(vec!["Fine"], parser.next().unwrap().unwrap().text);
+    }
+
+    #[test]
+    fn last_line_at_max_time() {
+        let mut parser = LrcParser::from("[307445734561825860:15.00]x".as_bytes());
+        let sub = parser.next().unwrap().unwrap();
+        assert_eq!(Timecode::MAX, sub.end);
+    }
+}
@@ -0,0 +1,20 @@
+//! MicroDVD (.sub) subtitles.
+//!
+//! MicroDVD subtitles are timed in frames, `{start}{end}text`, one
+//! subtitle per line with `|` separating the lines of its text. They are
+//! parsed into [`SubRip`](crate::SubRip) subtitles.
+
+mod parser;
+
+use std::io::Read;
+
+pub(crate) use parser::next_bracketed;
+pub use parser::{MicroDvdParser, DEFAULT_FPS};
+
+/// Create a new parser for `subtitle`, assuming [`DEFAULT_FPS`].
+///
+/// `subtitle` must be in MicroDVD (.sub) format. Use
+/// [`MicroDvdParser::with_fps`] for videos with other frame rates.
+pub fn open<T: Read>(subtitle: T) -> MicroDvdParser<T> {
+    MicroDvdParser::from(subtitle)
+}
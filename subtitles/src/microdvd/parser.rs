@@ -0,0 +1,189 @@
+use crate::{
+    error::{Error, ErrorKind},
+    lines::Lines,
+    subrip::format::{SubRip, Timecode},
+};
+use std::{error, io::Read, result};
+
+type Result<T> = result::Result<T, Box<dyn error::Error>>;
+type ParseResult<T> = result::Result<T, Error>;
+
+/// The frame rate used by [`open`](super::open).
+pub const DEFAULT_FPS: f64 = 25.0;
+
+/// An iterator over the subtitles of a MicroDVD (.sub) file.
+///
+/// Frame numbers are converted to timecodes using the frame rate of the
+/// video. Subtitles are numbered sequentially from 1.
+///
+/// This `struct` is created by [`open`](super::open) or
+/// [`with_fps`](MicroDvdParser::with_fps).
+pub struct MicroDvdParser<T: Read> {
+    subtitle: Lines<T>,
+    fps: f64,
+    position: usize,
+}
+
+impl<T: Read> MicroDvdParser<T> {
+    /// Create a new parser for a video running at `fps` frames per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fps` is not a finite number above zero.
+    pub fn with_fps(subtitle: T, fps: f64) -> Self {
+        assert!(
+            fps.is_finite() && fps > 0.0,
+            "frame rate must be finite and positive, got {}",
+            fps
+        );
+        MicroDvdParser {
+            subtitle: Lines::new(subtitle),
+            fps,
+            position: 0,
+        }
+    }
+
+    fn parse_next(&mut self) -> ParseResult<Option<SubRip>> {
+        let fps = self.fps;
+        next_bracketed(
+            &mut self.subtitle,
+            &mut self.position,
+            ('{', '}'),
+            |frame| Timecode::from_frames(frame, fps),
+        )
+    }
+}
+
+/// Parse the next non-blank `{start}{end}text` line of `lines` into the
+/// subtitle after `position`.
+///
+/// `delimiters` are the brackets around the times, `{` and `}` here, and
+/// `timecode` converts the times. Lines of the text are separated by `|`.
+pub(crate) fn next_bracketed<T: Read>(
+    lines: &mut Lines<T>,
+    position: &mut usize,
+    delimiters: (char, char),
+    timecode: impl Fn(u64) -> Timecode,
+) -> ParseResult<Option<SubRip>> {
+    let line = loop {
+        match lines.next_line() {
+            Ok(Some(line)) if line.trim().is_empty() => continue,
+            Ok(Some(line)) => break line,
+            Ok(None) => return Ok(None),
+            Err(err) => return Err(Error::new(ErrorKind::Io, err)),
+        }
+    };
+
+    *position += 1;
+    let (start, end, text) = split_bracketed(&line, delimiters)
+        .map_err(|err| Error::new(ErrorKind::InvalidTimecode, err))?;
+
+    Ok(Some(SubRip {
+        position: *position,
+        start: timecode(start),
+        end: timecode(end),
+        text: text.split('|').map(String::from).collect(),
+    }))
+}
+
+/// Split a `{start}{end}text` line, with `delimiters` as the brackets,
+/// into its parts.
+fn split_bracketed(line: &str, (open, close): (char, char)) -> Result<(u64, u64, &str)> {
+    let err = || {
+        format!(
+            "expected `{0}start{1}{0}end{1}text`, found `{2}`",
+            open, close, line
+        )
+    };
+
+    let (start, rest) = split_time(line, open, close).ok_or_else(err)?;
+    let (end, text) = split_time(rest, open, close).ok_or_else(err)?;
+
+    Ok((start.parse()?, end.parse()?, text))
+}
+
+/// Split `{time}rest` into `time` and `rest`.
+fn split_time(line: &str, open: char, close: char) -> Option<(&str, &str)> {
+    let line = line.strip_prefix(open)?;
+    let end = line.find(close)?;
+    Some((&line[..end], &line[end + 1..]))
+}
+
+impl<T: Read> From<T> for MicroDvdParser<T> {
+    fn from(subtitle: T) -> Self {
+        MicroDvdParser::with_fps(subtitle, DEFAULT_FPS)
+    }
+}
+
+impl<T: Read> Iterator for MicroDvdParser<T> {
+    type Item = ParseResult<SubRip>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parse_next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_subtitles() {
+        let subtitle = "\
+{25}{50}Hello|World
+
+{100}{150}Second
+";
+        let mut parser = MicroDvdParser::from(subtitle.as_bytes());
+
+        let first = parser.next().unwrap().unwrap();
+        assert_eq!(1, first.position);
+        assert_eq!("00:00:01,000", first.start.to_string());
+        assert_eq!("00:00:02,000", first.end.to_string());
+        assert_eq!(vec!["Hello", "World"], first.text);
+
+        let second = parser.next().unwrap().unwrap();
+        assert_eq!(2, second.position);
+        assert_eq!("00:00:04,000", second.start.to_string());
+        assert_eq!("00:00:06,000", second.end.to_string());
+        assert_eq!(vec!["Second"], second.text);
+
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn custom_fps() {
+        let subtitle = "{24}{48}Test";
+        let sub = MicroDvdParser::with_fps(subtitle.as_bytes(), 23.976)
+            .next()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!("00:00:01,001", sub.start.to_string());
+        assert_eq!("00:00:02,002", sub.end.to_string());
+    }
+
+    #[test]
+    fn invalid_line() {
+        let subtitle = "\
+{25}50}Broken
+{100}{150}Fine";
+        let mut parser = MicroDvdParser::from(subtitle.as_bytes());
+
+        let err = parser.next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("{25}50}Broken"));
+        assert_eq!(vec!["Fine"], parser.next().unwrap().unwrap().text);
+    }
+
+    #[test]
+    #[should_panic(expected = "frame rate must be finite and positive")]
+    fn zero_fps() {
+        MicroDvdParser::with_fps("{0}{25}Hello".as_bytes(), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "frame rate must be finite and positive")]
+    fn nan_fps() {
+        MicroDvdParser::with_fps("{0}{25}Hello".as_bytes(), f64::NAN);
+    }
+}
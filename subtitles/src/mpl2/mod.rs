@@ -0,0 +1,18 @@
+//! MPL2 (.txt) subtitles.
+//!
+//! MPL2 subtitles are timed in deciseconds, `[start][end]text`, one
+//! subtitle per line with `|` separating the lines of its text. They are
+//! parsed into [`SubRip`](crate::SubRip) subtitles.
+
+mod parser;
+
+use std::io::Read;
+
+pub use parser::Mpl2Parser;
+
+/// Create a new parser for `subtitle`.
+///
+/// `subtitle` must be in MPL2 format.
+pub fn open<T: Read>(subtitle: T) -> Mpl2Parser<T> {
+    Mpl2Parser::from(subtitle)
+}
@@ -0,0 +1,97 @@
+use crate::{
+    error::Error,
+    lines::Lines,
+    microdvd::next_bracketed,
+    subrip::format::{SubRip, Timecode},
+};
+use std::{io::Read, result, time::Duration};
+
+type ParseResult<T> = result::Result<T, Error>;
+
+/// An iterator over the subtitles of an MPL2 file.
+///
+/// Subtitles are numbered sequentially from 1. The `/` marking italic
+/// lines is kept in the text.
+///
+/// This `struct` is created by [`open`](super::open).
+pub struct Mpl2Parser<T: Read> {
+    subtitle: Lines<T>,
+    position: usize,
+}
+
+impl<T: Read> Mpl2Parser<T> {
+    fn parse_next(&mut self) -> ParseResult<Option<SubRip>> {
+        next_bracketed(
+            &mut self.subtitle,
+            &mut self.position,
+            ('[', ']'),
+            deciseconds,
+        )
+    }
+}
+
+fn deciseconds(time: u64) -> Timecode {
+    Timecode::from_duration(Duration::from_millis(time.saturating_mul(100)))
+}
+
+impl<T: Read> From<T> for Mpl2Parser<T> {
+    fn from(subtitle: T) -> Self {
+        Mpl2Parser {
+            subtitle: Lines::new(subtitle),
+            position: 0,
+        }
+    }
+}
+
+impl<T: Read> Iterator for Mpl2Parser<T> {
+    type Item = ParseResult<SubRip>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parse_next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn parse_subtitles() {
+        let subtitle = "\
+[123][456]Hello|World
+
+[600][625]/Second
+";
+        let mut parser = Mpl2Parser::from(subtitle.as_bytes());
+
+        let first = parser.next().unwrap().unwrap();
+        assert_eq!(1, first.position);
+        assert_eq!("00:00:12,300", first.start.to_string());
+        assert_eq!("00:00:45,600", first.end.to_string());
+        assert_eq!(vec!["Hello", "World"], first.text);
+
+        let second = parser.next().unwrap().unwrap();
+        assert_eq!(2, second.position);
+        assert_eq!("00:01:00,000", second.start.to_string());
+        assert_eq!("00:01:02,500", second.end.to_string());
+        assert_eq!(vec!["/Second"], second.text);
+
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn invalid_line() {
+        let subtitle = "\
+Broken
+[12]x[34]Broken too
+[100][150]Fine";
+        let mut parser = Mpl2Parser::from(subtitle.as_bytes());
+
+        let err = parser.next().unwrap().unwrap_err();
+        assert_eq!(ErrorKind::InvalidTimecode, err.kind());
+        assert!(err.to_string().contains("`Broken`"));
+        assert!(parser.next().unwrap().is_err());
+        assert_eq!(vec!["Fine"], parser.next().unwrap().unwrap().text);
+    }
+}
@@ -0,0 +1,35 @@
+//! SAMI (.smi) subtitles.
+//!
+//! SAMI files are HTML-like documents, with each subtitle introduced by a
+//! `<SYNC Start=ms>` tag and its text in a `<P>` block. Subtitles are
+//! parsed into [`SubRip`](crate::SubRip) subtitles, each one displayed
+//! until the next `<SYNC>` starts.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! # use std::io::Error;
+//! use std::fs::File;
+//!
+//! let file = File::open("/path/to/subtitle.smi")?;
+//! for subtitle in subtitles::sami::open(file) {
+//!     match subtitle {
+//!         Ok(sub) => println!("{}", sub),
+//!         Err(err) => eprintln!("{}", err),
+//!     }
+//! }
+//! # Ok::<(), Error>(())
+//! ```
+
+mod parser;
+
+use std::io::Read;
+
+pub use parser::{SamiParser, LAST_CUE_DURATION};
+
+/// Create a new parser for `subtitle`.
+///
+/// `subtitle` must be in SAMI (.smi) format.
+pub fn open<T: Read>(subtitle: T) -> SamiParser<T> {
+    SamiParser::from(subtitle)
+}
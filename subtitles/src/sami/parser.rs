@@ -0,0 +1,243 @@
+use crate::{
+    error::{Error, ErrorKind},
+    lines::Lines,
+    subrip::{
+        format::{SubRip, Timecode},
+        markup_lines,
+    },
+};
+use std::{collections::VecDeque, error, io::Read, result, time::Duration};
+
+type Result<T> = result::Result<T, Box<dyn error::Error>>;
+type ParseResult<T> = result::Result<T, Error>;
+
+/// How long the last subtitle is displayed, as no `<SYNC>` follows it.
+pub const LAST_CUE_DURATION: Duration = Duration::from_secs(5);
+
+/// An iterator over the subtitles of a SAMI (.smi) file.
+///
+/// SAMI is not line based, so the whole file is read on the first call to
+/// `next`. Errors are yielded first, in file order.
+///
+/// Each subtitle is displayed until the next `<SYNC>` starts. A `<SYNC>`
+/// without text, typically holding just `&nbsp;`, only ends the subtitle
+/// before it. Only the first `<P>` of a `<SYNC>` is read, which is the
+/// default language of multi-language files. `<BR>` breaks lines, and
+/// other tags are removed. Subtitles are numbered sequentially from 1.
+///
+/// This `struct` is created by [`open`](super::open).
+pub struct SamiParser<T: Read> {
+    subtitle: Lines<T>,
+    read: bool,
+    errors: VecDeque<Error>,
+    cues: VecDeque<(Duration, Vec<String>)>,
+    position: usize,
+}
+
+impl<T: Read> SamiParser<T> {
+    fn parse_next(&mut self) -> ParseResult<Option<SubRip>> {
+        if !self.read {
+            self.read_all();
+        }
+        if let Some(err) = self.errors.pop_front() {
+            return Err(err);
+        }
+
+        loop {
+            let (start, text) = match self.cues.pop_front() {
+                Some(cue) => cue,
+                None => return Ok(None),
+            };
+            if text.is_empty() {
+                continue;
+            }
+            let end = match self.cues.front() {
+                Some((next, _)) => *next,
+                None => start + LAST_CUE_DURATION,
+            };
+
+            self.position += 1;
+            return Ok(Some(SubRip {
+                position: self.position,
+                start: Timecode::from_duration(start),
+                end: Timecode::from_duration(end),
+                text,
+            }));
+        }
+    }
+
+    fn read_all(&mut self) {
+        self.read = true;
+
+        let mut document = String::new();
+        loop {
+            match self.subtitle.next_line() {
+                Ok(Some(line)) => {
+                    document.push_str(&line);
+                    document.push('\n');
+                }
+                Ok(None) => break,
+                Err(err) => {
+                    self.errors.push_back(Error::new(ErrorKind::Io, err));
+                    break;
+                }
+            }
+        }
+
+        // lowercasing ASCII keeps byte offsets, so they apply to `document`
+        let lowercase = document.to_ascii_lowercase();
+        let body_end = lowercase.find("</body").unwrap_or(document.len());
+        let syncs: Vec<usize> = lowercase[..body_end]
+            .match_indices("<sync")
+            .map(|(index, _)| index)
+            .collect();
+
+        for (i, &sync) in syncs.iter().enumerate() {
+            let end = syncs.get(i + 1).copied().unwrap_or(body_end);
+            let tag_len = match lowercase[sync..end].find('>') {
+                Some(len) => len,
+                None => {
+                    self.errors.push_back(Error::new(
+                        ErrorKind::InvalidTimecode,
+                        format!("unclosed tag `{}`", document[sync..end].trim()),
+                    ));
+                    continue;
+                }
+            };
+
+            match parse_start(&document[sync..sync + tag_len]) {
+                Ok(start) => self
+                    .cues
+                    .push_back((start, parse_text(&document[sync + tag_len + 1..end]))),
+                Err(err) => self
+                    .errors
+                    .push_back(Error::new(ErrorKind::InvalidTimecode, err)),
+            }
+        }
+        self.cues.make_contiguous().sort_by_key(|(start, _)| *start);
+    }
+}
+
+/// Parse the `Start` attribute, in milliseconds, of a `<SYNC` tag.
+fn parse_start(tag: &str) -> Result<Duration> {
+    let err = || format!("expected `Start=ms` in `{}>`", tag);
+
+    let attribute = tag.to_ascii_lowercase().find("start").ok_or_else(err)?;
+    let value = tag[attribute + "start".len()..]
+        .trim_start()
+        .strip_prefix('=')
+        .ok_or_else(err)?
+        .trim_start()
+        .trim_start_matches(&['"', '\''][..]);
+    let digits = value
+        .split(|c: char| !c.is_ascii_digit())
+        .next()
+        .unwrap_or_default();
+
+    let millis = digits.parse().map_err(|_| err())?;
+    Ok(Duration::from_millis(millis))
+}
+
+/// Extract the lines of text of the first `<P>` in `body`.
+fn parse_text(body: &str) -> Vec<String> {
+    let lowercase = body.to_ascii_lowercase();
+    let paragraph = match lowercase.find("<p") {
+        Some(start) => {
+            let end = lowercase[start + 2..]
+                .find("<p")
+                .map_or(body.len(), |len| start + 2 + len);
+            &body[start..end]
+        }
+        None => body,
+    };
+    markup_lines(paragraph)
+}
+
+impl<T: Read> From<T> for SamiParser<T> {
+    fn from(subtitle: T) -> Self {
+        SamiParser {
+            subtitle: Lines::new(subtitle),
+            read: false,
+            errors: VecDeque::new(),
+            cues: VecDeque::new(),
+            position: 0,
+        }
+    }
+}
+
+impl<T: Read> Iterator for SamiParser<T> {
+    type Item = ParseResult<SubRip>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parse_next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_subtitles() {
+        let subtitle = r#"<SAMI>
+<HEAD>
+<TITLE>Example</TITLE>
+<STYLE TYPE="text/css"><!--
+P { font-family: Arial; }
+.ENCC { Name: English; lang: en-US; }
+--></STYLE>
+</HEAD>
+<BODY>
+<SYNC Start=1000>
+  <P Class=ENCC>Hello,<br>
+  <i>world</i>!
+<SYNC Start="2500">
+  <P Class=ENCC>Tom &amp; Jerry
+  <P Class=FRCC>Tom et Jerry
+<SYNC Start=4000>
+  <P Class=ENCC>&nbsp;
+</BODY>
+</SAMI>
+"#;
+        let mut parser = SamiParser::from(subtitle.as_bytes());
+
+        let first = parser.next().unwrap().unwrap();
+        assert_eq!(1, first.position);
+        assert_eq!("00:00:01,000", first.start.to_string());
+        assert_eq!("00:00:02,500", first.end.to_string());
+        assert_eq!(vec!["Hello,", "world!"], first.text);
+
+        let second = parser.next().unwrap().unwrap();
+        assert_eq!(2, second.position);
+        assert_eq!("00:00:02,500", second.start.to_string());
+        assert_eq!("00:00:04,000", second.end.to_string());
+        assert_eq!(vec!["Tom & Jerry"], second.text);
+
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn last_cue() {
+        let subtitle = "<SAMI><BODY><SYNC Start=1000><P>Last</BODY></SAMI>";
+        let sub = SamiParser::from(subtitle.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!("00:00:06,000", sub.end.to_string());
+        assert_eq!(vec!["Last"], sub.text);
+    }
+
+    #[test]
+    fn invalid_start() {
+        let subtitle = "\
+<SYNC Start=soon><P>Broken
+<SYNC Start=1000><P>Fine";
+        let mut parser = SamiParser::from(subtitle.as_bytes());
+
+        let err = parser.next().unwrap().unwrap_err();
+        assert_eq!(ErrorKind::InvalidTimecode, err.kind());
+        assert_eq!(vec!["Fine"], parser.next().unwrap().unwrap().text);
+        assert!(parser.next().is_none());
+    }
+}
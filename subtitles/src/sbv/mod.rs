@@ -0,0 +1,35 @@
+//! SubViewer (.sbv) subtitles, as downloaded from YouTube.
+//!
+//! Each subtitle is a `H:MM:SS.mmm,H:MM:SS.mmm` timing line followed by
+//! its text, with subtitles separated by blank lines. They are parsed
+//! into [`SubRip`](crate::SubRip) subtitles.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! # use std::io::Error;
+//! use std::fs::File;
+//!
+//! let file = File::open("/path/to/subtitle.sbv")?;
+//! for subtitle in subtitles::sbv::open(file) {
+//!     match subtitle {
+//!         Ok(sub) => println!("{}", sub),
+//!         Err(err) => eprintln!("{}", err),
+//!     }
+//! }
+//! # Ok::<(), Error>(())
+//! ```
+
+mod parser;
+
+use std::io::Read;
+
+pub(crate) use parser::parse_timing;
+pub use parser::SbvParser;
+
+/// Create a new parser for `subtitle`.
+///
+/// `subtitle` must be in SubViewer (.sbv) format.
+pub fn open<T: Read>(subtitle: T) -> SbvParser<T> {
+    SbvParser::from(subtitle)
+}
@@ -0,0 +1,134 @@
+use crate::{
+    error::{Error, ErrorKind},
+    lines::Lines,
+    subrip::{
+        core::parse_time,
+        format::{SubRip, Timecode},
+    },
+};
+use std::{error, io::Read, result};
+
+type Result<T> = result::Result<T, Box<dyn error::Error>>;
+type ParseResult<T> = result::Result<T, Error>;
+
+/// An iterator over the subtitles of a SubViewer (.sbv) file.
+///
+/// SubViewer subtitles have no position, so they are numbered sequentially
+/// from 1. A subtitle with a malformed timing line is skipped as a whole.
+///
+/// This `struct` is created by [`open`](super::open).
+pub struct SbvParser<T: Read> {
+    subtitle: Lines<T>,
+    position: usize,
+}
+
+impl<T: Read> SbvParser<T> {
+    fn parse_next(&mut self) -> ParseResult<Option<SubRip>> {
+        let timing = loop {
+            match self.subtitle.next_line() {
+                Ok(Some(line)) if line.trim().is_empty() => continue,
+                Ok(Some(line)) => break line,
+                Ok(None) => return Ok(None),
+                Err(err) => return Err(Error::new(ErrorKind::Io, err)),
+            }
+        };
+
+        let mut text = Vec::new();
+        loop {
+            match self.subtitle.next_line() {
+                Ok(Some(line)) if line.is_empty() => break,
+                Ok(Some(line)) => text.push(line),
+                Ok(None) => break,
+                Err(err) => return Err(Error::new(ErrorKind::Io, err)),
+            }
+        }
+
+        self.position += 1;
+        let (start, end) =
+            parse_timing(&timing).map_err(|err| Error::new(ErrorKind::InvalidTimecode, err))?;
+
+        Ok(Some(SubRip {
+            position: self.position,
+            start,
+            end,
+            text,
+        }))
+    }
+}
+
+/// Parse a `H:MM:SS.mmm,H:MM:SS.mmm` timing line.
+pub(crate) fn parse_timing(line: &str) -> Result<(Timecode, Timecode)> {
+    let (start, end) = match line.find(',') {
+        Some(index) => (&line[..index], &line[index + 1..]),
+        None => return Err(format!("expected `start,end`, found `{}`", line).into()),
+    };
+
+    Ok((
+        parse_time(start.trim(), &['.'])?,
+        parse_time(end.trim(), &['.'])?,
+    ))
+}
+
+impl<T: Read> From<T> for SbvParser<T> {
+    fn from(subtitle: T) -> Self {
+        SbvParser {
+            subtitle: Lines::new(subtitle),
+            position: 0,
+        }
+    }
+}
+
+impl<T: Read> Iterator for SbvParser<T> {
+    type Item = ParseResult<SubRip>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parse_next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_subtitles() {
+        let subtitle = "\
+0:00:01.000,0:00:02.500
+Hello
+World
+
+1:02:03.456,1:02:05.000
+Second
+";
+        let mut parser = SbvParser::from(subtitle.as_bytes());
+
+        let first = parser.next().unwrap().unwrap();
+        assert_eq!(1, first.position);
+        assert_eq!("00:00:01,000", first.start.to_string());
+        assert_eq!("00:00:02,500", first.end.to_string());
+        assert_eq!(vec!["Hello", "World"], first.text);
+
+        let second = parser.next().unwrap().unwrap();
+        assert_eq!(2, second.position);
+        assert_eq!("01:02:03,456", second.start.to_string());
+        assert_eq!("01:02:05,000", second.end.to_string());
+        assert_eq!(vec!["Second"], second.text);
+
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn invalid_timing() {
+        let subtitle = "\
+0:00:01.000 0:00:02.500
+Broken
+
+0:00:03.000,0:00:04.000
+Fine";
+        let mut parser = SbvParser::from(subtitle.as_bytes());
+
+        let err = parser.next().unwrap().unwrap_err();
+        assert_eq!(ErrorKind::InvalidTimecode, err.kind());
+        assert_eq!(vec!["Fine"], parser.next().unwrap().unwrap().text);
+    }
+}
@@ -0,0 +1,330 @@
+use super::format::SubRip;
+use crate::error::{Error, ErrorKind};
+use std::{result, time::Duration};
+
+/// An iterator that stops at the first error.
+///
+/// The first `Err` is still yielded, every call to `next` after it
+/// returns `None`.
+///
+/// This `struct` is created by [`SubRipParser::fail_fast`].
+///
+/// [`SubRipParser::fail_fast`]: super::parser::SubRipParser::fail_fast
+pub struct FailFast<I> {
+    iter: I,
+    failed: bool,
+}
+
+impl<I> FailFast<I> {
+    pub(crate) fn new(iter: I) -> Self {
+        FailFast {
+            iter,
+            failed: false,
+        }
+    }
+}
+
+impl<I, T, E> Iterator for FailFast<I>
+where
+    I: Iterator<Item = result::Result<T, E>>,
+{
+    type Item = result::Result<T, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let item = self.iter.next()?;
+        self.failed = item.is_err();
+        Some(item)
+    }
+}
+
+/// An iterator that skips errors and yields only the parsed subtitles.
+///
+/// This `struct` is created by [`SubRipParser::valid_only`].
+///
+/// [`SubRipParser::valid_only`]: super::parser::SubRipParser::valid_only
+pub struct ValidOnly<I> {
+    iter: I,
+}
+
+impl<I> ValidOnly<I> {
+    pub(crate) fn new(iter: I) -> Self {
+        ValidOnly { iter }
+    }
+}
+
+impl<I, T, E> Iterator for ValidOnly<I>
+where
+    I: Iterator<Item = result::Result<T, E>>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.by_ref().find_map(|item| item.ok())
+    }
+}
+
+/// An iterator that skips subtitles without text.
+///
+/// This `struct` is created by [`SubRipParser::skip_empty`].
+///
+/// [`SubRipParser::skip_empty`]: super::parser::SubRipParser::skip_empty
+pub struct SkipEmpty<I> {
+    iter: I,
+}
+
+impl<I> SkipEmpty<I> {
+    pub(crate) fn new(iter: I) -> Self {
+        SkipEmpty { iter }
+    }
+}
+
+impl<I, E> Iterator for SkipEmpty<I>
+where
+    I: Iterator<Item = result::Result<SubRip, E>>,
+{
+    type Item = result::Result<SubRip, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .by_ref()
+            .find(|item| !matches!(item, Ok(sub) if sub.is_empty()))
+    }
+}
+
+/// An iterator that modifies every successfully parsed subtitle.
+///
+/// This `struct` is created by [`map_cues`].
+pub struct MapCues<I, F> {
+    iter: I,
+    f: F,
+}
+
+impl<I, F, E> Iterator for MapCues<I, F>
+where
+    I: Iterator<Item = result::Result<SubRip, E>>,
+    F: FnMut(&mut SubRip),
+{
+    type Item = result::Result<SubRip, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let f = &mut self.f;
+        self.iter.next().map(|item| {
+            item.map(|mut sub| {
+                f(&mut sub);
+                sub
+            })
+        })
+    }
+}
+
+/// Apply `f` to every `Ok` subtitle of `iter`.
+///
+/// Errors are passed through untouched.
+pub fn map_cues<I, F, E>(iter: I, f: F) -> MapCues<I::IntoIter, F>
+where
+    I: IntoIterator<Item = result::Result<SubRip, E>>,
+    F: FnMut(&mut SubRip),
+{
+    MapCues {
+        iter: iter.into_iter(),
+        f,
+    }
+}
+
+/// An iterator that fails on subtitles out of chronological order.
+///
+/// This `struct` is created by [`require_monotonic`].
+pub struct RequireMonotonic<I> {
+    iter: I,
+    latest: Option<Duration>,
+}
+
+impl<I> Iterator for RequireMonotonic<I>
+where
+    I: Iterator<Item = result::Result<SubRip, Error>>,
+{
+    type Item = result::Result<SubRip, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sub = match self.iter.next()? {
+            Ok(sub) => sub,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let start = sub.start.as_duration();
+        match self.latest {
+            Some(latest) if start < latest => Some(Err(Error::new(
+                ErrorKind::OutOfOrder,
+                format!(
+                    "subtitle {} starts at {}, before a previous subtitle",
+                    sub.position, sub.start
+                ),
+            ))),
+            _ => {
+                self.latest = Some(start);
+                Some(Ok(sub))
+            }
+        }
+    }
+}
+
+/// Fail on subtitles that start before a previous one.
+///
+/// An out-of-order subtitle is replaced by an [`ErrorKind::OutOfOrder`]
+/// error. Later subtitles are checked against the latest start seen so far.
+pub fn require_monotonic<I>(iter: I) -> RequireMonotonic<I::IntoIter>
+where
+    I: IntoIterator<Item = result::Result<SubRip, Error>>,
+{
+    RequireMonotonic {
+        iter: iter.into_iter(),
+        latest: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{format::Timecode, parser::SubRipParser},
+        *,
+    };
+
+    #[test]
+    fn fail_fast() {
+        let sub = "\
+1
+01:02:03,456 --> 07:08:09,101
+First
+
+2
+00:00:00,000
+
+3
+01:02:03,456 --> 07:08:09,101
+Third";
+
+        let mut parser = SubRipParser::from(sub.as_bytes()).fail_fast();
+
+        assert!(parser.next().unwrap().is_ok());
+        assert!(parser.next().unwrap().is_err());
+        assert!(parser.next().is_none());
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn valid_only() {
+        let sub = "\
+1
+01:02:03,456 --> 07:08:09,101
+First
+
+2
+00:00:00,000
+
+3
+01:02:03,456 --> 07:08:09,101
+Third";
+
+        let positions: Vec<usize> = SubRipParser::from(sub.as_bytes())
+            .valid_only()
+            .map(|sub| sub.position)
+            .collect();
+
+        assert_eq!(vec![1, 3], positions);
+    }
+
+    #[test]
+    fn skip_empty() {
+        let sub = "\
+1
+00:00:01,000 --> 00:00:02,000
+First
+
+2
+00:00:02,000 --> 00:00:03,000
+\x20
+
+3
+00:00:03,000 --> 00:00:04,000
+Third";
+
+        let positions: Vec<usize> = SubRipParser::from(sub.as_bytes())
+            .skip_empty()
+            .map(|sub| sub.unwrap().position)
+            .collect();
+
+        assert_eq!(vec![1, 3], positions);
+    }
+
+    #[test]
+    fn map_every_cue() {
+        let sub = "\
+1
+00:00:01,000 --> 00:00:02,000
+First
+
+2
+00:00:00,000
+
+3
+00:00:03,000 --> 00:00:04,000
+Third";
+
+        let offset = Timecode {
+            hours: 0,
+            minutes: 0,
+            seconds: 10,
+            milliseconds: 0,
+        };
+        let mut cues = map_cues(SubRipParser::from(sub.as_bytes()), |sub| {
+            sub.start = sub.start.saturating_add(&offset);
+            sub.end = sub.end.saturating_add(&offset);
+        });
+
+        let first = cues.next().unwrap().unwrap();
+        assert_eq!(
+            "00:00:11,000 --> 00:00:12,000",
+            format!("{} --> {}", first.start, first.end)
+        );
+
+        assert!(cues.next().unwrap().is_err());
+
+        let third = cues.next().unwrap().unwrap();
+        assert_eq!(
+            "00:00:13,000 --> 00:00:14,000",
+            format!("{} --> {}", third.start, third.end)
+        );
+
+        assert!(cues.next().is_none());
+    }
+
+    #[test]
+    fn out_of_order() {
+        let sub = "\
+1
+00:00:05,000 --> 00:00:06,000
+First
+
+2
+00:00:01,000 --> 00:00:02,000
+Second
+
+3
+00:00:07,000 --> 00:00:08,000
+Third";
+
+        let mut cues = require_monotonic(SubRipParser::from(sub.as_bytes()));
+
+        assert_eq!(1, cues.next().unwrap().unwrap().position);
+        assert_eq!(
+            ErrorKind::OutOfOrder,
+            cues.next().unwrap().unwrap_err().kind()
+        );
+        assert_eq!(3, cues.next().unwrap().unwrap().position);
+        assert!(cues.next().is_none());
+    }
+}
@@ -0,0 +1,146 @@
+use super::format::{SubRip, Timecode};
+use std::{collections::BTreeMap, time::Duration};
+
+/// Count subtitles by display duration.
+///
+/// Each key is the index of a bucket, bucket `k` holding the subtitles
+/// whose duration is in `[k * bucket, (k + 1) * bucket)`. Empty buckets
+/// are left out.
+///
+/// # Panics
+///
+/// Panics if `bucket` is zero.
+pub fn duration_histogram<I: IntoIterator<Item = SubRip>>(
+    subs: I,
+    bucket: Duration,
+) -> BTreeMap<u64, usize> {
+    assert!(bucket > Duration::ZERO, "bucket must be non-zero");
+
+    let mut histogram = BTreeMap::new();
+    for sub in subs {
+        let index = sub.duration().as_nanos() / bucket.as_nanos();
+        *histogram.entry(index as u64).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Find subtitles displayed for less than `min` or more than `max`.
+///
+/// Returns the position and duration of each such subtitle.
+pub fn flag_durations<I: IntoIterator<Item = SubRip>>(
+    subs: I,
+    min: Duration,
+    max: Duration,
+) -> Vec<(usize, Duration)> {
+    subs.into_iter()
+        .map(|sub| (sub.position, sub.duration()))
+        .filter(|&(_, duration)| duration < min || duration > max)
+        .collect()
+}
+
+/// Find the first subtitle displayed at `at`.
+///
+/// A subtitle is displayed from its `start` to its `end`, both inclusive.
+pub fn cue_at<I: IntoIterator<Item = SubRip>>(subs: I, at: Timecode) -> Option<SubRip> {
+    subs.into_iter()
+        .find(|sub| sub.start <= at && at <= sub.end)
+}
+
+/// Find every subtitle displayed at `at`.
+///
+/// Like [`cue_at`], but returns all overlapping subtitles.
+pub fn cues_at<I: IntoIterator<Item = SubRip>>(subs: I, at: Timecode) -> Vec<SubRip> {
+    subs.into_iter()
+        .filter(|sub| sub.start <= at && at <= sub.end)
+        .collect()
+}
+
+/// Find consecutive subtitles that overlap in time.
+///
+/// Returns the indices into `subs` of each subtitle starting before the
+/// previous one ends, paired with the index of that previous subtitle.
+pub fn find_overlaps(subs: &[SubRip]) -> Vec<(usize, usize)> {
+    subs.windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[1].start < pair[0].end)
+        .map(|(index, _)| (index, index + 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(millis: u64) -> SubRip {
+        SubRip::from_parts(1, Duration::ZERO, Duration::from_millis(millis), "")
+    }
+
+    fn cues() -> Vec<SubRip> {
+        vec![
+            SubRip::from_parts(1, Duration::from_secs(1), Duration::from_secs(3), "First"),
+            SubRip::from_parts(2, Duration::from_secs(2), Duration::from_secs(4), "Second"),
+            SubRip::from_parts(3, Duration::from_secs(6), Duration::from_secs(7), "Third"),
+        ]
+    }
+
+    fn at(secs: u8) -> Timecode {
+        Timecode {
+            hours: 0,
+            minutes: 0,
+            seconds: secs,
+            milliseconds: 0,
+        }
+    }
+
+    #[test]
+    fn histogram() {
+        let subs = vec![sub(500), sub(1000), sub(1500), sub(1999), sub(4200)];
+
+        let mut expected = BTreeMap::new();
+        expected.insert(0, 1);
+        expected.insert(1, 3);
+        expected.insert(4, 1);
+
+        assert_eq!(expected, duration_histogram(subs, Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn out_of_bounds_durations() {
+        let subs = vec![
+            SubRip::from_parts(1, Duration::ZERO, Duration::from_millis(300), "Short"),
+            SubRip::from_parts(2, Duration::from_secs(1), Duration::from_secs(3), "Fine"),
+            SubRip::from_parts(3, Duration::from_secs(3), Duration::from_secs(11), "Long"),
+        ];
+
+        let expected = vec![(1, Duration::from_millis(300)), (3, Duration::from_secs(8))];
+
+        assert_eq!(
+            expected,
+            flag_durations(subs, Duration::from_secs(1), Duration::from_secs(7))
+        );
+    }
+
+    #[test]
+    fn overlaps() {
+        assert_eq!(vec![(0, 1)], find_overlaps(&cues()));
+        assert!(find_overlaps(&cues()[1..]).is_empty());
+    }
+
+    #[test]
+    fn cue_at_time() {
+        assert_eq!(1, cue_at(cues(), at(2)).unwrap().position);
+        assert_eq!(3, cue_at(cues(), at(7)).unwrap().position);
+        assert!(cue_at(cues(), at(5)).is_none());
+    }
+
+    #[test]
+    fn cues_at_time() {
+        let positions: Vec<usize> = cues_at(cues(), at(2))
+            .iter()
+            .map(|sub| sub.position)
+            .collect();
+
+        assert_eq!(vec![1, 2], positions);
+        assert!(cues_at(cues(), at(5)).is_empty());
+    }
+}
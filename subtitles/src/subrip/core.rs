@@ -1,40 +1,102 @@
 use super::format::Timecode;
-use std::{error, result};
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use std::{
+    error,
+    num::{IntErrorKind, ParseIntError},
+    result,
+};
 
 pub type Result<T> = result::Result<T, Box<dyn error::Error>>;
 
 pub fn parse_position(line: String) -> Result<usize> {
-    let position = line.parse()?;
-    Ok(position)
+    line.parse().map_err(|err: ParseIntError| match err.kind() {
+        IntErrorKind::PosOverflow => format!("position number too large: `{}`", line).into(),
+        _ => err.into(),
+    })
 }
 
 pub fn parse_timecode(line: String) -> Result<(Timecode, Timecode)> {
-    let line: Vec<&str> = line.split(&[':', ',', ' '][..]).collect();
+    // some tools write `->` instead of `-->`
+    let (start, end) = ["-->", "->"]
+        .iter()
+        .find_map(|arrow| {
+            let index = line.find(arrow)?;
+            Some((&line[..index], &line[index + arrow.len()..]))
+        })
+        .ok_or("missing `-->` between start and end")?;
+
+    // anything after the end time, e.g. coordinates, is ignored
+    let end = end.split_whitespace().next().unwrap_or_default();
+
+    Ok((parse_time(start.trim(), &[','])?, parse_time(end, &[','])?))
+}
 
+/// Parse a time in the `HH:MM:SS,mmm` form, with one of `separators` in
+/// place of the `,`, e.g. `.` for `HH:MM:SS.mmm` as in WebVTT.
+///
+/// Hours may have any number of digits, the other fields must have exactly
+/// as many as shown, and minutes and seconds must be below 60.
+pub fn parse_time(time: &str, separators: &[char]) -> Result<Timecode> {
     let err = "wrong timecode format";
 
-    let start = Timecode {
-        hours: line.get(0).ok_or(err)?.parse()?,
-        minutes: line.get(1).ok_or(err)?.parse()?,
-        seconds: line.get(2).ok_or(err)?.parse()?,
-        milliseconds: line.get(3).ok_or(err)?.parse()?,
+    let (time, milliseconds) = time.split_at(time.rfind(separators).ok_or(err)?);
+    let fields: Vec<&str> = time.split(':').collect();
+    let (hours, minutes, seconds) = match fields[..] {
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        _ => return Err(err.into()),
     };
-    let end = Timecode {
-        hours: line.get(5).ok_or(err)?.parse()?,
-        minutes: line.get(6).ok_or(err)?.parse()?,
-        seconds: line.get(7).ok_or(err)?.parse()?,
-        milliseconds: line.get(8).ok_or(err)?.parse()?,
+
+    let timecode = Timecode {
+        hours: digits(hours, None).ok_or(err)?.parse()?,
+        minutes: digits(minutes, Some(2)).ok_or(err)?.parse()?,
+        seconds: digits(seconds, Some(2)).ok_or(err)?.parse()?,
+        milliseconds: digits(&milliseconds[1..], Some(3)).ok_or(err)?.parse()?,
     };
+    if timecode.minutes > 59 || timecode.seconds > 59 {
+        return Err("minutes and seconds must be below 60".into());
+    }
 
-    Ok((start, end))
+    Ok(timecode)
 }
 
+/// `field` if it is made of ASCII digits, exactly `len` of them if given.
+pub(crate) fn digits(field: &str, len: Option<usize>) -> Option<&str> {
+    let valid = !field.is_empty()
+        && field.bytes().all(|byte| byte.is_ascii_digit())
+        && len.map_or(true, |len| field.len() == len);
+    if valid {
+        Some(field)
+    } else {
+        None
+    }
+}
+
+/// Guess the encoding of BOM-less input from a sample of it.
+///
+/// The input is assumed to be UTF-8, unless `sample` contains malformed
+/// UTF-8, in which case `chardetng` makes the guess.
+pub fn guess_encoding(sample: &[u8]) -> &'static Encoding {
+    let mut decoder = UTF_8.new_decoder_without_bom_handling();
+    let mut decoded = String::with_capacity(sample.len() * 3);
+    let _ = decoder.decode_to_string(sample, &mut decoded, false);
+
+    if decoded.contains('\u{FFFD}') {
+        let mut detector = EncodingDetector::new();
+        detector.feed(sample, false);
+        detector.guess(None, true)
+    } else {
+        UTF_8
+    }
+}
+
+/// Remove a trailing `\n`, `\r\n` or lone `\r`.
 pub fn trim_newline(line: &mut String) {
     if line.ends_with('\n') {
         line.pop();
-        if line.ends_with('\r') {
-            line.pop();
-        }
+    }
+    if line.ends_with('\r') {
+        line.pop();
     }
 }
 
@@ -48,6 +110,18 @@ mod tests {
         assert!(parse_position(position).is_err());
     }
 
+    #[test]
+    fn position_too_large() {
+        let position = "1".repeat(40);
+
+        let err = parse_position(position.clone()).unwrap_err();
+
+        assert_eq!(
+            format!("position number too large: `{}`", position),
+            err.to_string()
+        );
+    }
+
     #[test]
     fn position() {
         let position = String::from("1433");
@@ -56,19 +130,57 @@ mod tests {
 
     #[test]
     fn bad_format_timecode() {
-        let timecode = String::from("00:00:0,500 --> 00:00:2,00");
+        for timecode in &[
+            "00:00:0,500 --> 00:00:02,000",
+            "00:00:00,500 --> 00:00:2,00",
+            "00:00:00:500 --> 00:00:02,000",
+            "00:00,500 --> 00:00:02,000",
+            "00:00:00:00,500 --> 00:00:02,000",
+            "00:00:00,5000 --> 00:00:02,000",
+            "00: 00:00,500 --> 00:00:02,000",
+            "00:00:+1,500 --> 00:00:02,000",
+            "00:00:60,000 --> 00:01:00,000",
+            "00:00:00.500 --> 00:00:02,000",
+            "00:00:00,500 --> 00:00:02.000",
+        ] {
+            assert!(
+                parse_timecode(timecode.to_string()).is_err(),
+                "{}",
+                timecode
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_timecode() {
+        let timecode = String::from("00:00:00,000");
+        assert!(parse_timecode(timecode).is_err());
+    }
+
+    #[test]
+    fn missing_arrow_timecode() {
+        let timecode = String::from("00:00:00,000 xyz 00:00:01,000");
+
+        let err = parse_timecode(timecode).unwrap_err();
+
+        assert_eq!("missing `-->` between start and end", err.to_string());
+    }
+
+    #[test]
+    fn short_arrow_timecode() {
+        let timecode = String::from("01:04:00,705 -> 01:04:02,145");
 
         let expected_start = Timecode {
-            hours: 0,
-            minutes: 0,
+            hours: 1,
+            minutes: 4,
             seconds: 0,
-            milliseconds: 500,
+            milliseconds: 705,
         };
         let expected_end = Timecode {
-            hours: 0,
-            minutes: 0,
+            hours: 1,
+            minutes: 4,
             seconds: 2,
-            milliseconds: 0,
+            milliseconds: 145,
         };
 
         let (start, end) = parse_timecode(timecode).unwrap();
@@ -78,32 +190,29 @@ mod tests {
     }
 
     #[test]
-    fn invalid_timecode() {
-        let timecode = String::from("00:00:00,000");
-        assert!(parse_timecode(timecode).is_err());
+    fn unspaced_arrow_timecode() {
+        for timecode in &["01:04:00,705-->01:04:02,145", "01:04:00,705->01:04:02,145"] {
+            let (start, end) = parse_timecode(timecode.to_string()).unwrap();
+
+            assert_eq!("01:04:00,705", start.to_string());
+            assert_eq!("01:04:02,145", end.to_string());
+        }
     }
 
     #[test]
     fn negative_timecode() {
         let timecode = String::from("00:-1:-58,-240 --> 00:-1:-55,-530");
+        assert!(parse_timecode(timecode).is_err());
+    }
 
-        let expected_start = Timecode {
-            hours: 0,
-            minutes: -1,
-            seconds: -58,
-            milliseconds: -240,
-        };
-        let expected_end = Timecode {
-            hours: 0,
-            minutes: -1,
-            seconds: -55,
-            milliseconds: -530,
-        };
+    #[test]
+    fn long_timecode() {
+        let timecode = String::from("130:04:00,705 --> 130:04:02,145");
 
         let (start, end) = parse_timecode(timecode).unwrap();
 
-        assert_eq!(expected_start, start);
-        assert_eq!(expected_end, end);
+        assert_eq!(130, start.hours);
+        assert_eq!("130:04:02,145", end.to_string());
     }
 
     #[test]
@@ -1,25 +1,228 @@
-use std::fmt;
+use super::{core::parse_time, text::strip_tags};
+use crate::{
+    error::{Error, ErrorKind},
+    webvtt::Cue,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt, str::FromStr, time::Duration};
 
-#[derive(Debug, PartialEq)]
+/// A point in time in the `HH:MM:SS,mmm` form.
+///
+/// Timecodes are ordered by the point in time they represent, so
+/// `00:00:90,000` comes after `00:01:00,000`. Different representations
+/// of the same point are ordered by hours, then minutes, seconds and
+/// milliseconds.
+///
+/// With the `serde` feature enabled, a timecode is serialized as a struct
+/// of its four fields, e.g. `{"hours":0,"minutes":1,"seconds":2,"milliseconds":3}`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Timecode {
-    pub hours: i8,
-    pub minutes: i8,
-    pub seconds: i8,
-    pub milliseconds: i16,
+    /// Hours
+    pub hours: u32,
+    /// Minutes
+    pub minutes: u8,
+    /// Seconds
+    pub seconds: u8,
+    /// Milliseconds
+    pub milliseconds: u16,
 }
 
-impl fmt::Display for Timecode {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl Timecode {
+    /// The largest representable timecode, `4294967295:59:59,999`.
+    pub const MAX: Timecode = Timecode {
+        hours: u32::MAX,
+        minutes: 59,
+        seconds: 59,
+        milliseconds: 999,
+    };
+
+    /// Add `other` to this timecode, clamping at [`Timecode::MAX`].
+    pub fn saturating_add(&self, other: &Timecode) -> Timecode {
+        Timecode::from_duration(self.as_duration() + other.as_duration())
+    }
+
+    /// Subtract `other` from this timecode, clamping at `00:00:00,000`.
+    pub fn saturating_sub(&self, other: &Timecode) -> Timecode {
+        Timecode::from_duration(self.as_duration().saturating_sub(other.as_duration()))
+    }
+
+    /// Add `other` to this timecode, or `None` past [`Timecode::MAX`].
+    pub fn checked_add(&self, other: &Timecode) -> Option<Timecode> {
+        let millis = self.total_milliseconds() + other.total_milliseconds();
+        if millis > Timecode::MAX.total_milliseconds() {
+            return None;
+        }
+        Some(Timecode::from_duration(Duration::from_millis(millis)))
+    }
+
+    /// Subtract `other` from this timecode, or `None` if it would go
+    /// before `00:00:00,000`.
+    pub fn checked_sub(&self, other: &Timecode) -> Option<Timecode> {
+        let millis = self
+            .total_milliseconds()
+            .checked_sub(other.total_milliseconds())?;
+        Some(Timecode::from_duration(Duration::from_millis(millis)))
+    }
+
+    fn total_milliseconds(&self) -> u64 {
+        ((u64::from(self.hours) * 60 + u64::from(self.minutes)) * 60 + u64::from(self.seconds))
+            * 1000
+            + u64::from(self.milliseconds)
+    }
+
+    /// Convert this timecode to the time elapsed since `00:00:00,000`.
+    pub fn as_duration(&self) -> Duration {
+        Duration::from_millis(self.total_milliseconds())
+    }
+
+    /// Create a timecode from the time elapsed since `00:00:00,000`.
+    ///
+    /// Sub-millisecond precision is truncated and durations past
+    /// [`Timecode::MAX`] saturate at it.
+    pub fn from_duration(duration: Duration) -> Timecode {
+        let millis = duration.as_millis();
+        if millis > Timecode::MAX.as_duration().as_millis() {
+            return Timecode::MAX;
+        }
+
+        Timecode {
+            hours: (millis / 3_600_000) as u32,
+            minutes: (millis / 60_000 % 60) as u8,
+            seconds: (millis / 1000 % 60) as u8,
+            milliseconds: (millis % 1000) as u16,
+        }
+    }
+
+    /// Carry overflowing fields into the larger units, so that
+    /// `00:00:75,1500` becomes `00:01:16,500`.
+    ///
+    /// Timecodes past [`Timecode::MAX`] saturate at it.
+    pub fn normalize(&mut self) {
+        *self = Timecode::from_duration(self.as_duration());
+    }
+
+    /// Convert this timecode to the nearest frame of a video running at
+    /// `fps` frames per second.
+    ///
+    /// Frame rates need not be integers: at NTSC's 23.976 fps,
+    /// `00:00:01,000` is frame 24, the nearest to the 23.976 frames shown
+    /// during that second.
+    ///
+    /// `fps` must be finite and positive, or the result is meaningless.
+    pub fn to_frames(&self, fps: f64) -> u64 {
+        (self.total_milliseconds() as f64 * fps / 1000.0).round() as u64
+    }
+
+    /// Create a timecode from frame `frames` of a video running at `fps`
+    /// frames per second, rounded to the nearest millisecond.
+    ///
+    /// Frames past [`Timecode::MAX`] saturate at it. `fps` must be finite
+    /// and positive, or the result is meaningless.
+    pub fn from_frames(frames: u64, fps: f64) -> Timecode {
+        let millis = (frames as f64 * 1000.0 / fps).round();
+        if millis >= Timecode::MAX.total_milliseconds() as f64 {
+            return Timecode::MAX;
+        }
+        Timecode::from_duration(Duration::from_millis(millis as u64))
+    }
+}
+
+impl Ord for Timecode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_milliseconds()
+            .cmp(&other.total_milliseconds())
+            .then(self.hours.cmp(&other.hours))
+            .then(self.minutes.cmp(&other.minutes))
+            .then(self.seconds.cmp(&other.seconds))
+            .then(self.milliseconds.cmp(&other.milliseconds))
+    }
+}
+
+impl PartialOrd for Timecode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for Timecode {
+    type Err = Error;
+
+    /// Parse a timecode in the `HH:MM:SS,mmm` form.
+    ///
+    /// `.` is accepted as the millisecond separator too, as in WebVTT.
+    fn from_str(time: &str) -> Result<Self, Self::Err> {
+        parse_time(time, &[',', '.']).map_err(|err| Error::new(ErrorKind::InvalidTimecode, err))
+    }
+}
+
+/// The precision of the fractional seconds in a formatted timecode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Precision {
+    /// `HH:MM:SS,mmm`
+    Milliseconds,
+    /// `HH:MM:SS.cc`, rounded to the nearest centisecond.
+    Centiseconds,
+    /// `HH:MM:SS`, rounded to the nearest second.
+    Seconds,
+}
+
+impl Timecode {
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, precision: Precision) -> fmt::Result {
+        match precision {
+            Precision::Milliseconds => self.write_millis(f, ','),
+            Precision::Centiseconds => {
+                let time = self.rounded(10);
+                write!(
+                    f,
+                    "{:02}:{:02}:{:02}.{:02}",
+                    time.hours,
+                    time.minutes,
+                    time.seconds,
+                    time.milliseconds / 10
+                )
+            }
+            Precision::Seconds => {
+                let time = self.rounded(1000);
+                write!(
+                    f,
+                    "{:02}:{:02}:{:02}",
+                    time.hours, time.minutes, time.seconds
+                )
+            }
+        }
+    }
+
+    /// Write as `HH:MM:SS` and the milliseconds, after `separator`.
+    pub(crate) fn write_millis<W: fmt::Write>(&self, out: &mut W, separator: char) -> fmt::Result {
         write!(
-            f,
-            "{:02}:{:02}:{:02},{:03}",
-            self.hours, self.minutes, self.seconds, self.milliseconds
+            out,
+            "{:02}:{:02}:{:02}{}{:03}",
+            self.hours, self.minutes, self.seconds, separator, self.milliseconds
         )
     }
+
+    /// Round to the nearest multiple of `unit` milliseconds.
+    fn rounded(&self, unit: u64) -> Timecode {
+        let millis = self.as_duration().as_millis() as u64;
+        let millis = (millis + unit / 2) / unit * unit;
+        Timecode::from_duration(Duration::from_millis(millis))
+    }
+}
+
+impl fmt::Display for Timecode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with(f, Precision::Milliseconds)
+    }
 }
 
 /// Representing a SubRip (.srt) file
-#[derive(Debug, PartialEq)]
+///
+/// `Display` separates lines with `\n`. The alternate flag, as in
+/// `format!("{:#}", sub)`, separates them with `\r\n` instead.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SubRip {
     /// Subtitle position
     pub position: usize,
@@ -31,19 +234,299 @@ pub struct SubRip {
     pub text: Vec<String>,
 }
 
+impl SubRip {
+    /// Create a subtitle from its start and end time.
+    ///
+    /// `text` is split into lines on `\n`.
+    pub fn from_parts(position: usize, start: Duration, end: Duration, text: &str) -> SubRip {
+        SubRip {
+            position,
+            start: Timecode::from_duration(start),
+            end: Timecode::from_duration(end),
+            text: text.lines().map(String::from).collect(),
+        }
+    }
+
+    /// Replace the text of this subtitle.
+    ///
+    /// `text` is split into lines on `\n` and `\r\n`.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.lines().map(String::from).collect();
+    }
+
+    /// The lines of this subtitle joined by `sep`.
+    pub fn text_joined(&self, sep: &str) -> String {
+        self.text.join(sep)
+    }
+
+    /// Trim whitespace around every line and drop blank lines.
+    pub fn trim_text(&mut self) {
+        self.text = self
+            .text
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect();
+    }
+
+    /// Whether the text has no lines, or only blank ones.
+    pub fn is_empty(&self) -> bool {
+        self.text.iter().all(|line| line.trim().is_empty())
+    }
+
+    /// Trim leading and trailing whitespace from every line of the text.
+    ///
+    /// Unlike [`trim_text`](SubRip::trim_text), empty lines are kept. Useful
+    /// to compare subtitles from files with different line endings or
+    /// trailing spaces.
+    pub fn trimmed(mut self) -> SubRip {
+        for line in &mut self.text {
+            let trimmed = line.trim();
+            if trimmed.len() != line.len() {
+                *line = trimmed.to_string();
+            }
+        }
+        self
+    }
+
+    /// Break lines at literal `\N` and `\n` markers, as left in the text
+    /// by some ASS to SubRip converters.
+    pub fn expand_line_breaks(&mut self) {
+        self.text = self
+            .text
+            .iter()
+            .flat_map(|line| line.split("\\N"))
+            .flat_map(|line| line.split("\\n"))
+            .map(String::from)
+            .collect();
+    }
+
+    /// Move this subtitle `offset` later, or earlier if `forward` is false.
+    ///
+    /// Shifting earlier clamps both timecodes at `00:00:00,000`.
+    pub fn shift(&mut self, offset: Duration, forward: bool) {
+        let shift = |time: &Timecode| {
+            let time = time.as_duration();
+            if forward {
+                Timecode::from_duration(time.saturating_add(offset))
+            } else {
+                Timecode::from_duration(time.saturating_sub(offset))
+            }
+        };
+
+        self.start = shift(&self.start);
+        self.end = shift(&self.end);
+    }
+
+    /// Like [`shift`](SubRip::shift), but takes and returns the subtitle,
+    /// so whole files can be shifted as they are streamed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let input = "1\n00:00:01,000 --> 00:00:02,000\nHello\n\n";
+    /// let mut output = Vec::new();
+    ///
+    /// let subs = subtitles::open(input.as_bytes())
+    ///     .filter_map(Result::ok)
+    ///     .map(|sub| sub.shifted(Duration::from_millis(1500), true));
+    /// subtitles::write(&mut output, subs)?;
+    ///
+    /// assert_eq!(
+    ///     "1\n00:00:02,500 --> 00:00:03,500\nHello\n\n",
+    ///     String::from_utf8(output).unwrap()
+    /// );
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn shifted(mut self, offset: Duration, forward: bool) -> SubRip {
+        self.shift(offset, forward);
+        self
+    }
+
+    /// Swap `start` and `end` if `end` precedes `start`.
+    pub fn fix_reversed(&mut self) {
+        if self.end < self.start {
+            std::mem::swap(&mut self.start, &mut self.end);
+        }
+    }
+
+    /// How long this subtitle is displayed.
+    ///
+    /// Returns `Duration::ZERO` if `end` precedes `start`.
+    pub fn duration(&self) -> Duration {
+        self.end
+            .as_duration()
+            .saturating_sub(self.start.as_duration())
+    }
+
+    /// The number of characters in the text, excluding formatting tags and
+    /// line breaks.
+    pub fn char_count(&self) -> usize {
+        self.text
+            .iter()
+            .map(|line| strip_tags(line).chars().count())
+            .sum()
+    }
+
+    /// The reading speed in characters per second.
+    ///
+    /// Returns `0.0` if the duration is zero.
+    pub fn reading_speed(&self) -> f64 {
+        let duration = self.duration();
+        if duration == Duration::ZERO {
+            0.0
+        } else {
+            self.char_count() as f64 / duration.as_secs_f64()
+        }
+    }
+
+    /// Re-break a two-line subtitle so its lines are of similar length.
+    ///
+    /// Lines are only broken between words. Subtitles with any other number
+    /// of lines are left untouched.
+    pub fn balance_lines(&mut self) {
+        if self.text.len() != 2 {
+            return;
+        }
+
+        let words: Vec<&str> = self
+            .text
+            .iter()
+            .flat_map(|line| line.split_whitespace())
+            .collect();
+        if words.len() < 2 {
+            return;
+        }
+
+        let lengths: Vec<usize> = words.iter().map(|word| word.chars().count()).collect();
+        let total: usize = lengths.iter().sum::<usize>() + words.len() - 1;
+
+        let mut best = (usize::MAX, 1);
+        for split in 1..words.len() {
+            let first = lengths[..split].iter().sum::<usize>() + split - 1;
+            let second = total - first - 1;
+            let difference = first.abs_diff(second);
+            if difference < best.0 {
+                best = (difference, split);
+            }
+        }
+
+        let (first, second) = words.split_at(best.1);
+        self.text = vec![first.join(" "), second.join(" ")];
+    }
+
+    /// Split this subtitle into cues of at most `max_chars` characters.
+    ///
+    /// The words of all lines are packed, in order, into single-line cues
+    /// shown one after the other. A word longer than `max_chars` gets a cue
+    /// of its own. The display time is shared out in proportion to the
+    /// length of each cue's text. A subtitle that already fits is returned
+    /// as is.
+    ///
+    /// Every cue keeps this subtitle's position, see
+    /// [`renumber`](crate::renumber) to number them.
+    pub fn split_by_length(self, max_chars: usize) -> Vec<SubRip> {
+        let words: Vec<&str> = self
+            .text
+            .iter()
+            .flat_map(|line| line.split_whitespace())
+            .collect();
+
+        let mut chunks: Vec<(String, usize)> = Vec::new();
+        for word in words {
+            let length = word.chars().count();
+            match chunks.last_mut() {
+                Some((chunk, chunk_length)) if *chunk_length + 1 + length <= max_chars => {
+                    chunk.push(' ');
+                    chunk.push_str(word);
+                    *chunk_length += 1 + length;
+                }
+                _ => chunks.push((word.to_string(), length)),
+            }
+        }
+        if chunks.len() < 2 {
+            return vec![self];
+        }
+
+        let start = self.start.as_duration().as_millis();
+        let duration = self.duration().as_millis();
+        let total: usize = chunks.iter().map(|(_, length)| length).sum();
+
+        let mut elapsed = 0;
+        let mut cue_start = self.start.clone();
+        chunks
+            .into_iter()
+            .map(|(text, length)| {
+                elapsed += length;
+                let end = start + duration * elapsed as u128 / total as u128;
+                let cue = SubRip {
+                    position: self.position,
+                    start: cue_start.clone(),
+                    end: Timecode::from_duration(Duration::from_millis(end as u64)),
+                    text: vec![text],
+                };
+                cue_start = cue.end.clone();
+                cue
+            })
+            .collect()
+    }
+
+    /// Format this subtitle as a WebVTT cue.
+    ///
+    /// The position becomes the cue identifier and the timecodes use `.`
+    /// as the millisecond separator, as in `00:00:01.500`.
+    pub fn to_vtt_cue(&self) -> String {
+        Cue::from(self.clone()).to_string()
+    }
+
+    /// Split this subtitle into its start time, end time and text.
+    ///
+    /// The lines of the text are joined by `\n`.
+    pub fn to_parts(&self) -> (Duration, Duration, String) {
+        (
+            self.start.as_duration(),
+            self.end.as_duration(),
+            self.text_joined("\n"),
+        )
+    }
+}
+
+impl SubRip {
+    pub(crate) fn display_with(&self, precision: Precision) -> WithPrecision<'_> {
+        WithPrecision {
+            sub: self,
+            precision,
+        }
+    }
+
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, precision: Precision) -> fmt::Result {
+        let newline = if f.alternate() { "\r\n" } else { "\n" };
+
+        write!(f, "{}{}", self.position, newline)?;
+        self.start.fmt_with(f, precision)?;
+        f.write_str(" --> ")?;
+        self.end.fmt_with(f, precision)?;
+        write!(f, "{}{}", newline, self.text_joined(newline))
+    }
+}
+
 impl fmt::Display for SubRip {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "\
-{}
-{} --> {}
-{}",
-            self.position,
-            self.start,
-            self.end,
-            self.text.join("\n")
-        )
+        self.fmt_with(f, Precision::Milliseconds)
+    }
+}
+
+pub(crate) struct WithPrecision<'a> {
+    sub: &'a SubRip,
+    precision: Precision,
+}
+
+impl fmt::Display for WithPrecision<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.sub.fmt_with(f, self.precision)
     }
 }
 
@@ -51,6 +534,57 @@ impl fmt::Display for SubRip {
 mod tests {
     use super::*;
 
+    #[test]
+    fn split_by_length() {
+        let sub = SubRip::from_parts(
+            7,
+            Duration::from_secs(10),
+            Duration::from_secs(14),
+            "Where are you going?\nI am going back home",
+        );
+
+        let cues = sub.split_by_length(20);
+
+        let expected = vec![
+            SubRip::from_parts(
+                7,
+                Duration::from_secs(10),
+                Duration::from_millis(12_000),
+                "Where are you going?",
+            ),
+            SubRip::from_parts(
+                7,
+                Duration::from_millis(12_000),
+                Duration::from_secs(14),
+                "I am going back home",
+            ),
+        ];
+        assert_eq!(expected, cues);
+    }
+
+    #[test]
+    fn split_short_enough() {
+        let sub = SubRip::from_parts(1, Duration::ZERO, Duration::from_secs(1), "Short\ntext");
+        assert_eq!(vec![sub.clone()], sub.split_by_length(42));
+    }
+
+    #[test]
+    fn vtt_cue() {
+        let sub = SubRip::from_parts(
+            3,
+            Duration::from_millis(1500),
+            Duration::from_millis(3_723_004),
+            "First\nSecond",
+        );
+
+        let expected = "\
+3
+00:00:01.500 --> 01:02:03.004
+First
+Second";
+        assert_eq!(expected, sub.to_vtt_cue());
+    }
+
     #[test]
     fn display_timecode() {
         let time = Timecode {
@@ -64,6 +598,168 @@ mod tests {
         assert_eq!(expected, format!("{}", time));
     }
 
+    #[test]
+    fn normalize() {
+        let mut time = Timecode {
+            hours: 0,
+            minutes: 0,
+            seconds: 75,
+            milliseconds: 1500,
+        };
+        time.normalize();
+
+        let expected = Timecode {
+            hours: 0,
+            minutes: 1,
+            seconds: 16,
+            milliseconds: 500,
+        };
+        assert_eq!(expected, time);
+    }
+
+    #[test]
+    fn frames() {
+        let second: Timecode = "00:00:01,000".parse().unwrap();
+        assert_eq!(25, second.to_frames(25.0));
+        assert_eq!(second, Timecode::from_frames(25, 25.0));
+
+        assert_eq!(24, second.to_frames(23.976));
+        assert_eq!(
+            "00:00:01,001",
+            Timecode::from_frames(24, 23.976).to_string()
+        );
+    }
+
+    #[test]
+    fn parse_timecode() {
+        let expected = Timecode {
+            hours: 1,
+            minutes: 4,
+            seconds: 0,
+            milliseconds: 705,
+        };
+
+        assert_eq!(expected, "01:04:00,705".parse().unwrap());
+        assert_eq!(expected, "01:04:00.705".parse().unwrap());
+    }
+
+    #[test]
+    fn parse_malformed_timecode() {
+        let err = "01:04".parse::<Timecode>().unwrap_err();
+        assert_eq!(ErrorKind::InvalidTimecode, err.kind());
+
+        assert!("01:04:00;705".parse::<Timecode>().is_err());
+    }
+
+    #[test]
+    fn compare_timecodes() {
+        let first = Timecode {
+            hours: 0,
+            minutes: 0,
+            seconds: 1,
+            milliseconds: 0,
+        };
+        let second = Timecode {
+            hours: 0,
+            minutes: 0,
+            seconds: 2,
+            milliseconds: 0,
+        };
+
+        assert!(first < second);
+        assert!(second > first);
+        assert_eq!(Ordering::Equal, first.cmp(&first));
+    }
+
+    #[test]
+    fn compare_unnormalized_timecodes() {
+        let ninety = Timecode {
+            hours: 0,
+            minutes: 0,
+            seconds: 90,
+            milliseconds: 0,
+        };
+        let sixty = Timecode {
+            hours: 0,
+            minutes: 1,
+            seconds: 0,
+            milliseconds: 0,
+        };
+        let also_ninety = Timecode {
+            hours: 0,
+            minutes: 1,
+            seconds: 30,
+            milliseconds: 0,
+        };
+
+        assert!(sixty < ninety);
+        assert!(ninety < also_ninety);
+        assert_ne!(also_ninety, ninety);
+    }
+
+    #[test]
+    fn duration_round_trip() {
+        let time = Timecode {
+            hours: 1,
+            minutes: 4,
+            seconds: 0,
+            milliseconds: 705,
+        };
+        let duration = Duration::from_millis(3_840_705);
+
+        assert_eq!(duration, time.as_duration());
+        assert_eq!(time, Timecode::from_duration(duration));
+    }
+
+    #[test]
+    fn saturating_add() {
+        let time = Timecode {
+            hours: 0,
+            minutes: 59,
+            seconds: 59,
+            milliseconds: 500,
+        };
+        let offset = Timecode {
+            hours: 0,
+            minutes: 0,
+            seconds: 1,
+            milliseconds: 700,
+        };
+        let expected = Timecode {
+            hours: 1,
+            minutes: 0,
+            seconds: 1,
+            milliseconds: 200,
+        };
+
+        assert_eq!(expected, time.saturating_add(&offset));
+        assert_eq!(Timecode::MAX, Timecode::MAX.saturating_add(&offset));
+    }
+
+    #[test]
+    fn saturating_sub() {
+        let time = Timecode {
+            hours: 0,
+            minutes: 0,
+            seconds: 1,
+            milliseconds: 0,
+        };
+        let offset = Timecode {
+            hours: 0,
+            minutes: 0,
+            seconds: 2,
+            milliseconds: 0,
+        };
+        let zero = Timecode {
+            hours: 0,
+            minutes: 0,
+            seconds: 0,
+            milliseconds: 0,
+        };
+
+        assert_eq!(zero, time.saturating_sub(&offset));
+    }
+
     #[test]
     fn display_subtitle() {
         let sub = SubRip {
@@ -91,4 +787,262 @@ Test";
 
         assert_eq!(expected, format!("{}", sub));
     }
+
+    #[test]
+    fn display_subtitle_crlf() {
+        let sub = SubRip {
+            position: 1,
+            start: Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                milliseconds: 456,
+            },
+            end: Timecode {
+                hours: 7,
+                minutes: 8,
+                seconds: 9,
+                milliseconds: 101,
+            },
+            text: vec![String::from("This is a"), String::from("Test")],
+        };
+
+        let lf = b"1\n01:02:03,456 --> 07:08:09,101\nThis is a\nTest";
+        let crlf = b"1\r\n01:02:03,456 --> 07:08:09,101\r\nThis is a\r\nTest";
+
+        assert_eq!(&lf[..], format!("{}", sub).as_bytes());
+        assert_eq!(&crlf[..], format!("{:#}", sub).as_bytes());
+    }
+
+    #[test]
+    fn set_text() {
+        let mut sub = SubRip::from_parts(1, Duration::ZERO, Duration::ZERO, "");
+        sub.set_text("First line\r\nSecond line\nThird line");
+
+        assert_eq!(vec!["First line", "Second line", "Third line"], sub.text);
+        assert_eq!("First line\nSecond line\nThird line", sub.text_joined("\n"));
+    }
+
+    #[test]
+    fn clone_subtitle() {
+        let sub = SubRip::from_parts(
+            1,
+            Duration::from_secs(1),
+            Duration::from_secs(2),
+            "This is a\nTest",
+        );
+
+        let mut copy = sub.clone();
+        assert_eq!(sub, copy);
+
+        copy.shift(Duration::from_secs(1), true);
+        assert_ne!(sub, copy);
+        assert_eq!(Duration::from_secs(1), sub.start.as_duration());
+    }
+
+    #[test]
+    fn reading_speed() {
+        let sub = SubRip::from_parts(
+            1,
+            Duration::from_secs(1),
+            Duration::from_secs(3),
+            "<i>Ten chars.</i>\nTen chars.",
+        );
+
+        assert_eq!(20, sub.char_count());
+        assert_eq!(10.0, sub.reading_speed());
+    }
+
+    #[test]
+    fn zero_duration_reading_speed() {
+        let sub = SubRip::from_parts(1, Duration::ZERO, Duration::ZERO, "Text");
+        assert_eq!(0.0, sub.reading_speed());
+    }
+
+    #[test]
+    fn text_joined() {
+        let sub = SubRip::from_parts(1, Duration::ZERO, Duration::ZERO, "a\nb");
+        assert_eq!("a b", sub.text_joined(" "));
+    }
+
+    #[test]
+    fn trim_text() {
+        let mut sub = SubRip::from_parts(1, Duration::ZERO, Duration::ZERO, "  Hello \n \nWorld\t");
+        sub.trim_text();
+
+        assert_eq!(vec!["Hello", "World"], sub.text);
+    }
+
+    #[test]
+    fn is_empty() {
+        let mut sub = SubRip::from_parts(1, Duration::ZERO, Duration::ZERO, "");
+        assert!(sub.is_empty());
+
+        sub.set_text(" \n\t");
+        assert!(sub.is_empty());
+
+        sub.set_text("\nHello");
+        assert!(!sub.is_empty());
+    }
+
+    #[test]
+    fn trimmed() {
+        let crlf = "1\r\n00:00:01,000 --> 00:00:02,000\r\nHello \r\n\tWorld\r\n";
+        let lf = "1\n00:00:01,000 --> 00:00:02,000\nHello\nWorld  \n";
+
+        let parse = |subtitle: &str| crate::parse_str(subtitle).next().unwrap().unwrap();
+
+        assert_ne!(parse(crlf), parse(lf));
+        assert_eq!(parse(crlf).trimmed(), parse(lf).trimmed());
+    }
+
+    #[test]
+    fn expand_line_breaks() {
+        let mut sub = SubRip::from_parts(1, Duration::ZERO, Duration::ZERO, "Line one\\NLine two");
+        sub.text.push(String::from("Line three\\nLine four"));
+        sub.expand_line_breaks();
+
+        assert_eq!(
+            vec!["Line one", "Line two", "Line three", "Line four"],
+            sub.text
+        );
+    }
+
+    #[test]
+    fn fix_reversed() {
+        let mut sub = SubRip::from_parts(1, Duration::from_secs(2), Duration::from_secs(1), "");
+        sub.fix_reversed();
+
+        assert_eq!(Duration::from_secs(1), sub.start.as_duration());
+        assert_eq!(Duration::from_secs(2), sub.end.as_duration());
+    }
+
+    #[test]
+    fn subtitle_duration() {
+        let sub = SubRip {
+            position: 1,
+            start: Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                milliseconds: 456,
+            },
+            end: Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 5,
+                milliseconds: 456,
+            },
+            text: vec![],
+        };
+
+        assert_eq!(Duration::from_secs(2), sub.duration());
+    }
+
+    #[test]
+    fn reversed_duration() {
+        let sub = SubRip::from_parts(1, Duration::from_secs(2), Duration::from_secs(1), "");
+        assert_eq!(Duration::ZERO, sub.duration());
+    }
+
+    #[test]
+    fn shift() {
+        let mut sub = SubRip::from_parts(1, Duration::from_secs(10), Duration::from_secs(12), "");
+
+        sub.shift(Duration::from_secs(5), false);
+        assert_eq!("00:00:05,000", sub.start.to_string());
+        assert_eq!("00:00:07,000", sub.end.to_string());
+
+        sub.shift(Duration::from_secs(6), false);
+        assert_eq!("00:00:00,000", sub.start.to_string());
+        assert_eq!("00:00:01,000", sub.end.to_string());
+
+        sub.shift(Duration::from_millis(1500), true);
+        assert_eq!("00:00:01,500", sub.start.to_string());
+        assert_eq!("00:00:02,500", sub.end.to_string());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let sub = SubRip::from_parts(
+            7,
+            Duration::from_millis(62_003),
+            Duration::from_millis(64_500),
+            "This is a\nTest",
+        );
+
+        let json = serde_json::to_string(&sub).unwrap();
+        assert_eq!(
+            r#"{"position":7,"start":{"hours":0,"minutes":1,"seconds":2,"milliseconds":3},"end":{"hours":0,"minutes":1,"seconds":4,"milliseconds":500},"text":["This is a","Test"]}"#,
+            json
+        );
+        assert_eq!(sub, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn balance_lines() {
+        let mut sub = SubRip::from_parts(
+            1,
+            Duration::ZERO,
+            Duration::ZERO,
+            "This is a very long first line\nshort",
+        );
+        sub.balance_lines();
+
+        assert_eq!(vec!["This is a very long", "first line short"], sub.text);
+    }
+
+    #[test]
+    fn parts_round_trip() {
+        let start = Duration::from_millis(3_840_705);
+        let end = Duration::from_millis(3_842_145);
+        let text = "This is a\nTest";
+
+        let sub = SubRip::from_parts(1433, start, end, text);
+
+        assert_eq!(
+            Timecode {
+                hours: 1,
+                minutes: 4,
+                seconds: 0,
+                milliseconds: 705,
+            },
+            sub.start
+        );
+        assert_eq!(vec!["This is a", "Test"], sub.text);
+        assert_eq!((start, end, String::from(text)), sub.to_parts());
+    }
+
+    #[test]
+    fn deduplicate() {
+        use std::collections::HashSet;
+
+        let sub = SubRip::from_parts(
+            1,
+            Duration::from_secs(1),
+            Duration::from_secs(2),
+            "Same\ntext",
+        );
+        let subs: HashSet<SubRip> = vec![sub.clone(), sub].into_iter().collect();
+
+        assert_eq!(1, subs.len());
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let a: Timecode = "00:00:59,500".parse().unwrap();
+        let b: Timecode = "00:00:00,700".parse().unwrap();
+
+        assert_eq!(
+            Some(String::from("00:01:00,200")),
+            a.checked_add(&b).map(|t| t.to_string())
+        );
+        assert_eq!(
+            Some(String::from("00:00:58,800")),
+            a.checked_sub(&b).map(|t| t.to_string())
+        );
+        assert_eq!(None, b.checked_sub(&a));
+        assert_eq!(None, Timecode::MAX.checked_add(&b));
+    }
 }
@@ -1,10 +1,34 @@
-mod core;
-mod error;
+mod adapter;
+mod analysis;
+pub(crate) mod core;
 pub mod format;
 mod parser;
+mod text;
+mod transform;
+mod writer;
 
-use parser::SubRipParser;
-use std::io::Read;
+use crate::error::Error;
+use encoding_rs::Encoding;
+#[cfg(feature = "flate2")]
+use flate2::read::GzDecoder;
+use format::SubRip;
+#[cfg(feature = "flate2")]
+use std::io::{BufRead, BufReader};
+use std::{
+    fs::{self, File},
+    io::{self, Read},
+    path::Path,
+};
+
+pub use adapter::{
+    map_cues, require_monotonic, FailFast, MapCues, RequireMonotonic, SkipEmpty, ValidOnly,
+};
+pub use analysis::{cue_at, cues_at, duration_histogram, find_overlaps, flag_durations};
+pub use parser::SubRipParser;
+pub(crate) use text::markup_lines;
+pub use text::{decode_entities, join_sentences, strip_tags};
+pub use transform::{enforce_min_gap, merge_overlapping, renumber};
+pub use writer::{to_lrc, to_string, to_webvtt, write, Writer};
 
 /// Create a new parser for `subtitle`.
 ///
@@ -12,3 +36,216 @@ use std::io::Read;
 pub fn open<T: Read>(subtitle: T) -> SubRipParser<T> {
     SubRipParser::from(subtitle)
 }
+
+/// Create a new parser for `subtitle`, decompressing it first if it is
+/// gzip compressed.
+///
+/// `subtitle` must be in SubRip (.srt) format, gzip compressed or not, as
+/// told by the `1f 8b` magic bytes that start gzip data. The parser reads
+/// through the buffer used to look for them, so uncompressed input is
+/// buffered only once. Requires the `flate2` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::io::Error;
+/// use std::fs::File;
+///
+/// let file = File::open("/path/to/subtitle.srt.gz")?;
+/// for subtitle in subtitles::open_auto(file)? {
+///     println!("{}", subtitle.unwrap());
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+#[cfg(feature = "flate2")]
+pub fn open_auto<'a, T: Read + 'a>(
+    subtitle: T,
+) -> io::Result<SubRipParser<Box<dyn BufRead + 'a>, Box<dyn BufRead + 'a>>> {
+    let mut subtitle = BufReader::new(subtitle);
+    let subtitle: Box<dyn BufRead + 'a> = if subtitle.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        Box::new(BufReader::new(GzDecoder::new(subtitle)))
+    } else {
+        Box::new(subtitle)
+    };
+    Ok(SubRipParser::from_buffered(subtitle))
+}
+
+/// Parse every subtitle of `subtitle`, stopping at the first error.
+///
+/// `subtitle` must be in SubRip (.srt) format.
+///
+/// # Example
+///
+/// ```
+/// let subtitle = "1\n00:00:01,000 --> 00:00:02,000\nHello\n";
+///
+/// let subs = subtitles::parse_all(subtitle.as_bytes())?;
+/// assert_eq!(1, subs.len());
+/// # Ok::<(), subtitles::Error>(())
+/// ```
+pub fn parse_all<T: Read>(subtitle: T) -> Result<Vec<SubRip>, Error> {
+    SubRipParser::from(subtitle).collect()
+}
+
+/// Create a new parser for the in-memory `subtitle`.
+///
+/// `subtitle` must be in SubRip (.srt) format. This is the same as
+/// [`open`] over `subtitle.as_bytes()`.
+///
+/// # Example
+///
+/// ```
+/// let subtitle = "1\n00:00:01,000 --> 00:00:02,000\nHello\n";
+///
+/// let sub = subtitles::parse_str(subtitle).next().unwrap().unwrap();
+/// assert_eq!(vec!["Hello"], sub.text);
+/// ```
+pub fn parse_str(subtitle: &str) -> SubRipParser<&[u8]> {
+    SubRipParser::from(subtitle.as_bytes())
+}
+
+/// Open the file at `path` and create a new parser for it.
+///
+/// The file must be in SubRip (.srt) format.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::io::Error;
+/// for subtitle in subtitles::open_path("/path/to/subtitle.srt")? {
+///     println!("{}", subtitle.unwrap());
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub fn open_path<P: AsRef<Path>>(path: P) -> io::Result<SubRipParser<File>> {
+    File::open(path).map(SubRipParser::from)
+}
+
+/// Replace the SubRip file at `path`, read as `original`, with `subs`.
+///
+/// `subs` are encoded as `encoding`, which should be the
+/// [`encoding`](SubRipParser::encoding) `original` was parsed with, and
+/// keep its line endings and BOM. The file keeps its permissions, and a
+/// symlink is followed to rewrite its target. The new contents go to a
+/// temporary file next to it first, which is then renamed over it, so an
+/// error never leaves it truncated.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::io::Error;
+/// use std::fs;
+///
+/// let original = fs::read("/path/to/subtitle.srt")?;
+/// let mut parser = subtitles::open(&original[..]);
+/// let mut subs: Vec<_> = parser.by_ref().map(|sub| sub.unwrap()).collect();
+/// subtitles::renumber(&mut subs);
+///
+/// let encoding = parser.encoding().unwrap_or(encoding_rs::UTF_8);
+/// subtitles::rewrite_path("/path/to/subtitle.srt", &original, encoding, subs)?;
+/// # Ok::<(), Error>(())
+/// ```
+pub fn rewrite_path<P: AsRef<Path>, I: IntoIterator<Item = SubRip>>(
+    path: P,
+    original: &[u8],
+    encoding: &'static Encoding,
+    subs: I,
+) -> io::Result<()> {
+    let contents = writer::encode_like(original, encoding, subs);
+
+    let path = fs::canonicalize(path)?;
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let result = fs::write(&temp, contents)
+        .and_then(|_| fs::set_permissions(&temp, fs::metadata(&path)?.permissions()))
+        .and_then(|_| fs::rename(&temp, &path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn parse_whole_file() {
+        let subtitle = "\
+1
+00:00:01,000 --> 00:00:02,000
+First
+
+2
+00:00:03,000 --> 00:00:04,000
+Second
+";
+        assert_eq!(2, parse_all(subtitle.as_bytes()).unwrap().len());
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn open_gzip() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let subtitle = "1\n00:00:01,000 --> 00:00:02,000\nHello\n";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(subtitle.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        for input in &[&compressed[..], subtitle.as_bytes()] {
+            let subs: Vec<SubRip> = open_auto(*input).unwrap().map(|sub| sub.unwrap()).collect();
+            assert_eq!(1, subs.len());
+            assert_eq!(vec!["Hello"], subs[0].text);
+        }
+    }
+
+    #[test]
+    fn parse_corrupt_file() {
+        let subtitle = "\
+1
+00:00:01,000 --> 00:00:02,000
+First
+
+two
+00:00:03,000 --> 00:00:04,000
+Second
+";
+        let err = parse_all(subtitle.as_bytes()).unwrap_err();
+        assert_eq!(ErrorKind::InvalidPosition, err.kind());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rewrite_symlinked_file() {
+        use std::{env, os::unix::fs::PermissionsExt, process};
+
+        let dir = env::temp_dir().join(format!("subtitles-{}-rewrite", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let (path, link) = (dir.join("movie.srt"), dir.join("link.srt"));
+        let original = "1\r\n00:00:01,000 --> 00:00:02,000\r\nOld\r\n";
+        fs::write(&path, original).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+
+        let mut sub = parse_all(original.as_bytes()).unwrap().remove(0);
+        sub.set_text("New");
+        rewrite_path(&link, original.as_bytes(), encoding_rs::UTF_8, vec![sub]).unwrap();
+
+        let expected = "1\r\n00:00:01,000 --> 00:00:02,000\r\nNew\r\n\r\n";
+        assert_eq!(expected, fs::read_to_string(&path).unwrap());
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(0o600, mode & 0o777);
+        assert_eq!(2, fs::read_dir(&dir).unwrap().count());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,57 +1,321 @@
 use super::{
+    adapter::{FailFast, SkipEmpty, ValidOnly},
     core::*,
-    error::{Error, ErrorKind},
     format::SubRip,
 };
-use encoding_rs::{Decoder, Encoding, UTF_16LE, UTF_8};
+use crate::error::{Error, ErrorKind, Warning};
+use encoding_rs::{Decoder, Encoding, UTF_16BE, UTF_16LE, UTF_8};
 use std::{
-    io::{BufRead, BufReader, Read},
-    result,
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Read},
+    marker::PhantomData,
+    result, slice,
 };
 
 type ParseResult<T> = result::Result<T, Error>;
 
-pub struct SubRipParser<T: Read> {
-    subtitle: BufReader<T>,
+/// An iterator over the subtitles of a SubRip (.srt) file.
+///
+/// This `struct` is created by [`open`](super::open). `B` is the buffered
+/// reader it reads `T` through, which is the input itself for
+/// [`from_buffered`](SubRipParser::from_buffered).
+pub struct SubRipParser<T: Read, B: BufRead = BufReader<T>> {
+    subtitle: B,
+    input: PhantomData<T>,
     decoder: Option<Decoder>,
+    smart_encoding: bool,
+    check_separators: bool,
+    recover: bool,
+    strict: bool,
+    blank_text_lines: bool,
+    missing_positions: bool,
+    preserve_raw: bool,
+    // lines the last subtitle was parsed from, with `preserve_raw`
+    last_raw: Option<String>,
+    // position of the last subtitle parsed, 0 before the first one
+    last_position: usize,
+    // lines read ahead and put back, with their byte offsets
+    lookahead: VecDeque<(String, u64)>,
+    // number of the last line read
+    line: usize,
+    // byte offset of the start of the last line read
+    line_offset: u64,
+    // bytes read from the input so far
+    consumed: u64,
+    // undecoded bytes of the last line read, kept across calls to
+    // `next_line` to reuse the allocation
+    buf: Vec<u8>,
+    // byte ending each line, `\r` for classic Mac OS files
+    newline: u8,
+    // blank lines since the text of the previous subtitle,
+    // `None` before the first subtitle
+    blank_lines: Option<usize>,
+    warnings: Vec<Warning>,
 }
 
 impl<T: Read> SubRipParser<T> {
+    /// Create a parser that decodes `subtitle` as `encoding`.
+    ///
+    /// Unlike [`From`], which picks the encoding from the BOM, this never
+    /// looks for a BOM. Use it for BOM-less files in a legacy encoding,
+    /// such as `encoding_rs::WINDOWS_1252`. This is a shorthand for
+    /// [`force_encoding`](SubRipParser::force_encoding).
+    pub fn with_encoding(subtitle: T, encoding: &'static Encoding) -> Self {
+        SubRipParser::from(subtitle).force_encoding(encoding)
+    }
+}
+
+impl<R: BufRead> SubRipParser<R, R> {
+    /// Create a parser reading from `subtitle`, which is already buffered.
+    ///
+    /// Unlike [`From`], this doesn't wrap `subtitle` in a `BufReader`,
+    /// which would buffer it twice.
+    pub fn from_buffered(subtitle: R) -> Self {
+        SubRipParser::with_reader(subtitle)
+    }
+}
+
+impl<T: Read, B: BufRead> SubRipParser<T, B> {
+    fn with_reader(subtitle: B) -> Self {
+        SubRipParser {
+            subtitle,
+            input: PhantomData,
+            decoder: None,
+            smart_encoding: false,
+            check_separators: false,
+            recover: false,
+            strict: false,
+            blank_text_lines: false,
+            missing_positions: false,
+            preserve_raw: false,
+            last_raw: None,
+            last_position: 0,
+            lookahead: VecDeque::new(),
+            line: 0,
+            line_offset: 0,
+            consumed: 0,
+            buf: Vec::new(),
+            newline: b'\n',
+            blank_lines: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Stop the iteration after the first error.
+    ///
+    /// By default the parser yields an error for a malformed subtitle and
+    /// continues with the next line. The returned iterator yields the first
+    /// error and then ends.
+    pub fn fail_fast(self) -> FailFast<Self> {
+        FailFast::new(self)
+    }
+
+    /// Skip malformed subtitles instead of yielding errors for them.
+    ///
+    /// The returned iterator yields the subtitles themselves rather than
+    /// `Result`s, for consumers with no use for the errors.
+    pub fn valid_only(self) -> ValidOnly<Self> {
+        ValidOnly::new(self)
+    }
+
+    /// Skip subtitles whose text is empty or only whitespace.
+    ///
+    /// Errors are still yielded.
+    pub fn skip_empty(self) -> SkipEmpty<Self> {
+        SkipEmpty::new(self)
+    }
+
+    /// Decode the input as `encoding`.
+    ///
+    /// By default the encoding is picked from the BOM, or is UTF-8 without
+    /// one. With this option the input is decoded as `encoding` and no BOM
+    /// is looked for. Call it before parsing anything.
+    pub fn force_encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.decoder = Some(encoding.new_decoder_without_bom_handling());
+        self
+    }
+
+    /// Guess the encoding of BOM-less input that is not valid UTF-8.
+    ///
+    /// Without a BOM the input is decoded as UTF-8. When this option is
+    /// enabled and the beginning of the input doesn't decode cleanly as
+    /// UTF-8, the encoding suggested by `chardetng` is used instead.
+    pub fn smart_encoding(mut self, enabled: bool) -> Self {
+        self.smart_encoding = enabled;
+        self
+    }
+
+    /// Report subtitles not separated by exactly one blank line.
+    ///
+    /// Multiple blank lines between subtitles are always accepted. With
+    /// this option enabled, each one is also recorded as a
+    /// [`Warning::Separator`], see [`warnings`](SubRipParser::warnings).
+    pub fn check_separators(mut self, enabled: bool) -> Self {
+        self.check_separators = enabled;
+        self
+    }
+
+    /// Resume at the next subtitle after an error.
+    ///
+    /// By default the parser continues right after the line that failed,
+    /// so the rest of a malformed subtitle is mistaken for the next one.
+    /// With this option enabled, the rest of the malformed subtitle, up to
+    /// the next blank line, is skipped. The error is still yielded.
+    pub fn recover(mut self, enabled: bool) -> Self {
+        self.recover = enabled;
+        self
+    }
+
+    /// Reject subtitles that don't end after they start.
+    ///
+    /// By default a subtitle such as `00:00:02,000 --> 00:00:01,000` is
+    /// accepted as is. With this option enabled, it is skipped and an
+    /// [`ErrorKind::InvalidTimecode`] error is yielded instead.
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
+
+    /// Keep blank lines that are part of the text of a subtitle.
+    ///
+    /// By default a blank line ends the text of a subtitle. With this
+    /// option enabled, blank lines only end it when followed by the position
+    /// and timecode of the next subtitle, or by the end of the input. Any
+    /// other blank lines are kept in the text as empty lines.
+    pub fn blank_text_lines(mut self, enabled: bool) -> Self {
+        self.blank_text_lines = enabled;
+        self
+    }
+
+    /// Accept subtitles without a position line.
+    ///
+    /// By default every subtitle must start with its position. With this
+    /// option enabled, a subtitle starting with its timecode instead is
+    /// numbered one after the subtitle before it, or 1 if it is the first.
+    pub fn missing_positions(mut self, enabled: bool) -> Self {
+        self.missing_positions = enabled;
+        self
+    }
+
+    /// Keep the lines each subtitle was parsed from, see
+    /// [`last_raw`](SubRipParser::last_raw).
+    ///
+    /// This lets a tool write back untouched subtitles verbatim. By default
+    /// the lines are not kept, which saves copying them.
+    pub fn preserve_raw(mut self, enabled: bool) -> Self {
+        self.preserve_raw = enabled;
+        self
+    }
+
+    /// The encoding the input is decoded from.
+    ///
+    /// The encoding is detected when the first line is read, so this is
+    /// `None` before that, unless it was given to
+    /// [`force_encoding`](SubRipParser::force_encoding).
+    pub fn encoding(&self) -> Option<&'static Encoding> {
+        self.decoder.as_ref().map(Decoder::encoding)
+    }
+
+    /// The lines the last subtitle returned was parsed from, joined by
+    /// `\n`.
+    ///
+    /// Only set with [`preserve_raw`](SubRipParser::preserve_raw) enabled,
+    /// and `None` after an error.
+    pub fn last_raw(&self) -> Option<&str> {
+        self.last_raw.as_deref()
+    }
+
+    /// The warnings recorded so far.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
     fn parse_next(&mut self) -> ParseResult<Option<SubRip>> {
+        self.last_raw = None;
+
         // Parse position
         let line = match self.skip_empty_lines() {
             Ok(Some(line)) => line,
             Ok(None) => return Ok(None),
-            Err(err) => return Err(Error::new(ErrorKind::InvalidPosition, err)),
+            Err(err) => return Err(Error::new(ErrorKind::Io, err)),
+        };
+        let mut raw = Vec::new();
+        if self.preserve_raw {
+            raw.push(line.clone());
+        }
+        let blank_lines = self.blank_lines.take();
+        let (position, timecode) = if self.is_missing_position(&line) {
+            (self.last_position + 1, Some(line))
+        } else {
+            let position =
+                parse_position(line).map_err(|err| Error::new(ErrorKind::InvalidPosition, err))?;
+            (position, None)
         };
-        let position =
-            parse_position(line).map_err(|err| Error::new(ErrorKind::InvalidPosition, err))?;
+
+        if let Some(blank_lines) = blank_lines {
+            if self.check_separators && blank_lines != 1 {
+                self.warnings.push(Warning::Separator {
+                    position,
+                    blank_lines,
+                });
+            }
+        }
 
         // Parse timecode
-        let line = match self.skip_empty_lines() {
-            Ok(Some(line)) => line,
-            Ok(None) => return Ok(None),
-            Err(err) => return Err(Error::new(ErrorKind::InvalidTimecode, err)),
+        let line = match timecode {
+            Some(line) => line,
+            None => match self.skip_empty_lines() {
+                Ok(Some(line)) => {
+                    if self.preserve_raw {
+                        raw.push(line.clone());
+                    }
+                    line
+                }
+                Ok(None) => return Ok(None),
+                Err(err) => return Err(Error::new(ErrorKind::Io, err)),
+            },
         };
         let (start, end) =
             parse_timecode(line).map_err(|err| Error::new(ErrorKind::InvalidTimecode, err))?;
+        let (timecode_line, timecode_offset) = (self.line, self.line_offset);
 
         // Parse text
         let mut text = Vec::new();
+        self.blank_lines = Some(0);
         loop {
             match self.next_line() {
                 Ok(Some(line)) => {
-                    if line.is_empty() {
-                        break;
+                    if !line.is_empty() {
+                        text.push(line);
+                    } else if self.blank_text_lines {
+                        match self.read_blank_lines() {
+                            Ok(Some(blank_lines)) => text.extend(blank_lines),
+                            Ok(None) => break,
+                            Err(err) => return Err(Error::new(ErrorKind::Io, err)),
+                        }
                     } else {
-                        text.push(line)
+                        self.blank_lines = Some(1);
+                        break;
                     }
                 }
                 Ok(None) => break,
-                Err(err) => return Err(Error::new(ErrorKind::InvalidText, err)),
+                Err(err) => return Err(Error::new(ErrorKind::Io, err)),
             }
         }
 
+        if self.strict && end <= start {
+            let err = format!("end {} is not after start {}", end, start);
+            return Err(
+                Error::new(ErrorKind::InvalidTimecode, err).at(timecode_line, timecode_offset)
+            );
+        }
+
+        if self.preserve_raw {
+            raw.extend(text.iter().cloned());
+            self.last_raw = Some(raw.join("\n"));
+        }
+
+        self.last_position = position;
         Ok(Some(SubRip {
             position,
             start,
@@ -60,6 +324,62 @@ impl<T: Read> SubRipParser<T> {
         }))
     }
 
+    /// Read the blank lines following a blank line in the text.
+    ///
+    /// Returns the blank lines if they are part of the text, or `None` if
+    /// they separate it from the next subtitle. The first non-blank lines
+    /// are put back either way.
+    fn read_blank_lines(&mut self) -> Result<Option<Vec<String>>> {
+        let mut blank_lines = vec![String::new()];
+        let position = loop {
+            match self.next_line()? {
+                Some(line) if line.is_empty() => blank_lines.push(line),
+                Some(line) => break line,
+                None => return Ok(None),
+            }
+        };
+        let position_offset = self.line_offset;
+
+        let timecode = self.next_line()?;
+        let timecode_offset = self.line_offset;
+        let is_header = self.is_missing_position(&position)
+            || parse_position(position.clone()).is_ok()
+                && timecode
+                    .clone()
+                    .is_some_and(|timecode| parse_timecode(timecode).is_ok());
+
+        self.put_back(position, position_offset);
+        if let Some(timecode) = timecode {
+            self.put_back(timecode, timecode_offset);
+        }
+
+        if is_header {
+            self.blank_lines = Some(blank_lines.len());
+            Ok(None)
+        } else {
+            Ok(Some(blank_lines))
+        }
+    }
+
+    /// Whether `line` is a timecode standing in for a missing position.
+    fn is_missing_position(&self, line: &str) -> bool {
+        self.missing_positions && parse_timecode(line.to_string()).is_ok()
+    }
+
+    fn put_back(&mut self, line: String, offset: u64) {
+        self.line -= 1;
+        self.lookahead.push_back((line, offset));
+    }
+
+    fn skip_block(&mut self) {
+        while let Ok(Some(line)) = self.next_line() {
+            if line.is_empty() {
+                self.blank_lines = Some(1);
+                break;
+            }
+        }
+    }
+
     fn skip_empty_lines(&mut self) -> Result<Option<String>> {
         loop {
             match self.next_line()? {
@@ -67,6 +387,9 @@ impl<T: Read> SubRipParser<T> {
                     if !line.is_empty() {
                         break Ok(Some(line));
                     }
+                    if let Some(blank_lines) = &mut self.blank_lines {
+                        *blank_lines += 1;
+                    }
                 }
                 None => break Ok(None),
             }
@@ -74,46 +397,165 @@ impl<T: Read> SubRipParser<T> {
     }
 
     fn next_line(&mut self) -> Result<Option<String>> {
-        let mut buf = Vec::new();
-        self.subtitle.read_until(b'\n', &mut buf)?;
-
-        let decoder = self.decoder.get_or_insert_with(|| {
-            let (encoding, _) = Encoding::for_bom(&buf).unwrap_or((UTF_8, 3));
-            Encoding::new_decoder_with_bom_removal(encoding)
-        });
+        // counted up front, so a read error refers to the failed line
+        self.line += 1;
+        if let Some((line, offset)) = self.lookahead.pop_front() {
+            self.line_offset = offset;
+            return Ok(Some(line));
+        }
+        self.line_offset = self.consumed;
 
-        // in this case new line character is \x0A\x00
-        // and we have already read until \x0A
-        if decoder.encoding() == UTF_16LE {
-            self.subtitle.read_until(b'\x00', &mut buf)?;
+        let buf = &mut self.buf;
+        buf.clear();
+        if self.consumed == 0 {
+            self.newline = read_first_line(&mut self.subtitle, buf)?;
+        } else {
+            self.subtitle.read_until(self.newline, buf)?;
         }
 
+        // a BOM never contains \x0A, so the first line holds all of it
+        // no matter how little the underlying reader returns per read
+        let decoder = match self.decoder {
+            Some(ref mut decoder) => decoder,
+            None => {
+                let encoding = match Encoding::for_bom(buf) {
+                    Some((encoding, _)) => encoding,
+                    None if self.smart_encoding => {
+                        let mut sample = buf.clone();
+                        sample.extend_from_slice(self.subtitle.fill_buf()?);
+                        guess_encoding(&sample)
+                    }
+                    None => UTF_8,
+                };
+                self.decoder
+                    .insert(Encoding::new_decoder_with_bom_removal(encoding))
+            }
+        };
+
+        // in UTF-16 a \x0A or \x0D byte may be half of any code unit,
+        // so keep reading until a whole newline unit
+        let newline: &[u8] = if decoder.encoding() == UTF_16LE {
+            self.newline = b'\n';
+            finish_utf16_line(&mut self.subtitle, buf, [b'\x0A', b'\x00'])?;
+            b"\x0A\x00"
+        } else if decoder.encoding() == UTF_16BE {
+            self.newline = b'\n';
+            finish_utf16_line(&mut self.subtitle, buf, [b'\x00', b'\x0A'])?;
+            b"\x00\x0A"
+        } else {
+            slice::from_ref(&self.newline)
+        };
+        self.consumed += buf.len() as u64;
+
         if buf.is_empty() {
+            self.line -= 1;
             Ok(None)
         } else {
-            let mut line = String::with_capacity(buf.len());
-            let _ = decoder.decode_to_string(&buf, &mut line, false);
+            // blank lines are common and need no allocation
+            if newline.len() == 1 && buf.iter().all(|&b| b == b'\r' || b == b'\n') {
+                return Ok(Some(String::new()));
+            }
+            let capacity = decoder
+                .max_utf8_buffer_length(buf.len())
+                .unwrap_or(buf.len());
+            let mut line = String::with_capacity(capacity);
+            // every line ends with a complete character, except for a
+            // truncated one at the end of the input, which must be flushed;
+            // only the last line can lack a newline, so there's no need to
+            // wait for more input to tell
+            let last = !buf.ends_with(newline) || buf.len() % newline.len() != 0;
+            let _ = decoder.decode_to_string(buf, &mut line, last);
             trim_newline(&mut line);
 
+            // files concatenated together have a BOM mid-stream, which
+            // decodes to a leading zero-width no-break space
+            if line.starts_with('\u{FEFF}') {
+                let bom_len = line.len() - line.trim_start_matches('\u{FEFF}').len();
+                line.drain(..bom_len);
+            }
             Ok(Some(line))
         }
     }
 }
 
+/// Read the first line into `buf` and find the byte ending the lines.
+///
+/// The line ends at the first `\n`, `\r\n` or lone `\r`, which may be
+/// split across reads. The byte is `\r` for the lone `\r` line endings of
+/// classic Mac OS files, and `\n` otherwise.
+fn read_first_line<R: BufRead>(reader: &mut R, buf: &mut Vec<u8>) -> io::Result<u8> {
+    loop {
+        let available = reader.fill_buf()?;
+        let end = match available.iter().position(|&b| b == b'\r' || b == b'\n') {
+            Some(end) => end,
+            None if available.is_empty() => return Ok(b'\n'),
+            None => {
+                let len = available.len();
+                buf.extend_from_slice(available);
+                reader.consume(len);
+                continue;
+            }
+        };
+
+        let newline = available[end];
+        buf.extend_from_slice(&available[..=end]);
+        reader.consume(end + 1);
+        if newline == b'\n' {
+            return Ok(b'\n');
+        }
+        // the \n of a \r\n may only come with the next read
+        if reader.fill_buf()?.first() == Some(&b'\n') {
+            buf.push(b'\n');
+            reader.consume(1);
+            return Ok(b'\n');
+        }
+        return Ok(b'\r');
+    }
+}
+
+/// Read into `buf` until it ends with the `newline` code unit.
+///
+/// `buf` must start at a code unit boundary, which is the case for every
+/// line since the BOM and all code units are two bytes long.
+fn finish_utf16_line<R: BufRead>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    newline: [u8; 2],
+) -> io::Result<()> {
+    while buf.len() % 2 != 0 || !buf.ends_with(&newline) {
+        let read = if buf.len() % 2 != 0 {
+            reader.take(1).read_to_end(buf)?
+        } else {
+            reader.read_until(b'\n', buf)?
+        };
+
+        if read == 0 {
+            break;
+        }
+    }
+    Ok(())
+}
+
 impl<T: Read> From<T> for SubRipParser<T> {
     fn from(subtitle: T) -> Self {
-        SubRipParser {
-            subtitle: BufReader::new(subtitle),
-            decoder: None,
-        }
+        SubRipParser::with_reader(BufReader::new(subtitle))
     }
 }
 
-impl<T: Read> Iterator for SubRipParser<T> {
+impl<T: Read, B: BufRead> Iterator for SubRipParser<T, B> {
     type Item = ParseResult<SubRip>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.parse_next().transpose()
+        let result = self.parse_next().map_err(|err| match err.line() {
+            Some(_) => err,
+            None => err.at(self.line, self.line_offset),
+        });
+        // `blank_lines` is only set once the text is read, so an error
+        // raised after that, such as a reversed timecode, has nothing to skip
+        if result.is_err() && self.recover && self.blank_lines.is_none() {
+            self.skip_block();
+        }
+        result.transpose()
     }
 }
 
@@ -154,6 +596,181 @@ Test";
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn bom_on_its_own_line() {
+        let subtitle = b"\
+\xEF\xBB\xBF
+1433
+01:04:00,705 --> 01:04:02,145
+This is a
+Test";
+        let subtitle = Cursor::new(subtitle);
+
+        let expected = SubRip {
+            position: 1433,
+            start: Timecode {
+                hours: 1,
+                minutes: 4,
+                seconds: 0,
+                milliseconds: 705,
+            },
+            end: Timecode {
+                hours: 1,
+                minutes: 4,
+                seconds: 2,
+                milliseconds: 145,
+            },
+            text: vec![String::from("This is a"), String::from("Test")],
+        };
+
+        let mut parser = SubRipParser::from(subtitle);
+
+        assert_eq!(expected, parser.next().unwrap().unwrap());
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn bom_mid_stream() {
+        let subtitle = "\
+\u{FEFF}1
+00:00:01,000 --> 00:00:02,000
+First file
+
+\u{FEFF}2
+00:00:03,000 --> 00:00:04,000
+Second file
+";
+        let subs: Vec<SubRip> = SubRipParser::from(subtitle.as_bytes())
+            .map(|sub| sub.unwrap())
+            .collect();
+
+        assert_eq!(2, subs.len());
+        assert_eq!(2, subs[1].position);
+        assert_eq!(vec!["Second file"], subs[1].text);
+    }
+
+    #[test]
+    fn from_buffered() {
+        let subtitle = Cursor::new("1\n00:00:01,000 --> 00:00:02,000\nHello\n");
+        let subs: Vec<SubRip> = SubRipParser::from_buffered(subtitle)
+            .map(|sub| sub.unwrap())
+            .collect();
+
+        assert_eq!(1, subs.len());
+        assert_eq!(vec!["Hello"], subs[0].text);
+    }
+
+    #[test]
+    fn cr_line_endings() {
+        let subtitle = "1\r00:00:01,000 --> 00:00:02,000\rFirst\rLine\r\r\
+2\r00:00:03,000 --> 00:00:04,000\rSecond\r";
+        let subs: Vec<SubRip> = SubRipParser::from(subtitle.as_bytes())
+            .map(|sub| sub.unwrap())
+            .collect();
+
+        assert_eq!(2, subs.len());
+        assert_eq!("00:00:02,000", subs[0].end.to_string());
+        assert_eq!(vec!["First", "Line"], subs[0].text);
+        assert_eq!(2, subs[1].position);
+        assert_eq!(vec!["Second"], subs[1].text);
+    }
+
+    #[test]
+    fn cr_line_endings_with_short_reads() {
+        let subtitle = "1\r00:00:01,000 --> 00:00:02,000\rFirst\rLine\r\r\
+2\r00:00:03,000 --> 00:00:04,000\rSecond\r";
+        let subs: Vec<SubRip> = SubRipParser::from(ByteByByte(subtitle.as_bytes()))
+            .map(|sub| sub.unwrap())
+            .collect();
+
+        assert_eq!(2, subs.len());
+        assert_eq!(vec!["First", "Line"], subs[0].text);
+        assert_eq!(vec!["Second"], subs[1].text);
+
+        let subtitle = "1\r\n00:00:01,000 --> 00:00:02,000\r\nFirst\r\n";
+        let subs: Vec<SubRip> = SubRipParser::from(ByteByByte(subtitle.as_bytes()))
+            .map(|sub| sub.unwrap())
+            .collect();
+        assert_eq!(vec!["First"], subs[0].text);
+    }
+
+    /// A reader returning a single byte per call to `read`.
+    struct ByteByByte<'a>(&'a [u8]);
+
+    impl Read for ByteByByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.0.len().min(buf.len()).min(1);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn bom_with_short_reads() {
+        let mut subtitle = vec![b'\xFF', b'\xFE'];
+        subtitle.extend(
+            "\
+1
+00:00:01,000 --> 00:00:02,000
+Tęst"
+                .encode_utf16()
+                .flat_map(|x| x.to_le_bytes().to_vec()),
+        );
+
+        let expected = SubRip {
+            position: 1,
+            start: Timecode {
+                hours: 0,
+                minutes: 0,
+                seconds: 1,
+                milliseconds: 0,
+            },
+            end: Timecode {
+                hours: 0,
+                minutes: 0,
+                seconds: 2,
+                milliseconds: 0,
+            },
+            text: vec![String::from("Tęst")],
+        };
+
+        let actual = SubRipParser::from(ByteByByte(&subtitle))
+            .next()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn utf_8_multibyte_with_short_reads() {
+        // longer than the buffer of `BufReader`, so the line is read in parts
+        let text = "😀é€".repeat(1000);
+        let subtitle = format!("1\n00:00:01,000 --> 00:00:02,000\n{}\n{}", text, text);
+
+        let actual = SubRipParser::from(ByteByByte(subtitle.as_bytes()))
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![text.clone(), text.clone()], actual.text);
+
+        let actual = SubRipParser::from(subtitle.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(vec![text.clone(), text], actual.text);
+    }
+
+    #[test]
+    fn truncated_utf_8() {
+        let subtitle = b"1\n00:00:01,000 --> 00:00:02,000\nCaf\xC3";
+
+        let actual = SubRipParser::from(&subtitle[..]).next().unwrap().unwrap();
+
+        assert_eq!(vec![String::from("Caf\u{FFFD}")], actual.text);
+    }
+
     #[test]
     fn utf_16be_with_bom() {
         let mut bom = vec![b'\xFE', b'\xFF'];
@@ -163,8 +780,7 @@ Test";
 This is ą
 Tęst"
             .encode_utf16()
-            .map(|x| x.to_be_bytes().to_vec())
-            .flatten()
+            .flat_map(|x| x.to_be_bytes().to_vec())
             .collect();
 
         bom.extend(subtitle);
@@ -201,8 +817,7 @@ Tęst"
 This is ą
 Tęst"
             .encode_utf16()
-            .map(|x| x.to_le_bytes().to_vec())
-            .flatten()
+            .flat_map(|x| x.to_le_bytes().to_vec())
             .collect();
 
         bom.extend(subtitle);
@@ -230,6 +845,112 @@ Tęst"
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn detected_encoding() {
+        let mut subtitle = vec![b'\xFF', b'\xFE'];
+        subtitle.extend(
+            "1\n00:00:01,000 --> 00:00:02,000\nHello"
+                .encode_utf16()
+                .flat_map(|x| x.to_le_bytes().to_vec()),
+        );
+
+        let mut parser = SubRipParser::from(Cursor::new(subtitle));
+        assert_eq!(None, parser.encoding());
+
+        parser.next().unwrap().unwrap();
+        assert_eq!(Some(UTF_16LE), parser.encoding());
+    }
+
+    #[test]
+    fn utf_16_multiple_entries() {
+        // U+010A and U+0A05 both contain a \x0A byte
+        let subtitle = "\
+1
+00:00:01,000 --> 00:00:02,000
+Ċ ਅ
+
+2
+00:00:03,000 --> 00:00:04,000
+Second
+";
+
+        for &big_endian in &[true, false] {
+            let mut bytes = if big_endian {
+                vec![b'\xFE', b'\xFF']
+            } else {
+                vec![b'\xFF', b'\xFE']
+            };
+            bytes.extend(subtitle.encode_utf16().flat_map(|x| {
+                if big_endian {
+                    x.to_be_bytes()
+                } else {
+                    x.to_le_bytes()
+                }
+            }));
+
+            let subs: Vec<SubRip> = SubRipParser::from(&bytes[..])
+                .map(|sub| sub.unwrap())
+                .collect();
+
+            assert_eq!(2, subs.len());
+            assert_eq!(vec![String::from("Ċ ਅ")], subs[0].text);
+            assert_eq!(2, subs[1].position);
+            assert_eq!("00:00:03,000", subs[1].start.to_string());
+            assert_eq!(vec![String::from("Second")], subs[1].text);
+        }
+    }
+
+    #[test]
+    fn smart_encoding() {
+        let subtitle = b"\
+1
+00:00:01,000 --> 00:00:02,000
+Le caf\xE9 \xE9tait tr\xE8s bon, merci beaucoup.";
+
+        let expected = vec![String::from("Le café était très bon, merci beaucoup.")];
+
+        let actual = SubRipParser::from(&subtitle[..]).next().unwrap().unwrap();
+        assert_ne!(expected, actual.text);
+
+        let actual = SubRipParser::from(&subtitle[..])
+            .smart_encoding(true)
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(expected, actual.text);
+    }
+
+    #[test]
+    fn explicit_encoding() {
+        let subtitle = b"\
+1
+00:00:01,000 --> 00:00:02,000
+Cr\xE8me br\xFBl\xE9e, \xA9 2004";
+
+        let actual = SubRipParser::with_encoding(&subtitle[..], encoding_rs::WINDOWS_1252)
+            .next()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(vec![String::from("Crème brûlée, © 2004")], actual.text);
+    }
+
+    #[test]
+    fn buffered_explicit_encoding() {
+        let subtitle = b"\
+1
+00:00:01,000 --> 00:00:02,000
+Cr\xE8me br\xFBl\xE9e";
+
+        let actual = SubRipParser::from_buffered(&subtitle[..])
+            .force_encoding(encoding_rs::WINDOWS_1252)
+            .next()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(vec![String::from("Crème brûlée")], actual.text);
+    }
+
     #[test]
     fn parse_subtitle() {
         let sub = "\
@@ -380,6 +1101,260 @@ This is a Test";
         assert_eq!(expected, parser.next().unwrap().unwrap());
     }
 
+    #[test]
+    fn error_line() {
+        let sub = "\
+1
+00:00:00,000 --> 00:00:01,000
+First
+
+2
+00:00:01,000 --> 00:00:02,000
+Second
+Line
+
+3
+00:00:02,000 -> -> 00:00:03,000
+Third";
+
+        let err = SubRipParser::from(sub.as_bytes())
+            .find_map(|sub| sub.err())
+            .unwrap();
+
+        assert_eq!(Some(11), err.line());
+        assert!(err.to_string().starts_with("line 11: invalid timecode: "));
+    }
+
+    #[test]
+    fn error_byte_offset() {
+        let sub = "\u{FEFF}1\r\n00:00:00,000 --> 00:00:01,000\r\nFïrst\r\n\r\n2\r\nbroken\r\n";
+        let offset = sub.find("broken").unwrap() as u64;
+
+        let err = SubRipParser::from(sub.as_bytes())
+            .find_map(|sub| sub.err())
+            .unwrap();
+
+        assert_eq!(Some(6), err.line());
+        assert_eq!(Some(offset), err.byte_offset());
+    }
+
+    #[test]
+    fn strict() {
+        let sub = "\
+1
+00:00:02,000 --> 00:00:01,000
+Reversed
+
+2
+00:00:03,000 --> 00:00:03,000
+Empty
+
+3
+00:00:04,000 --> 00:00:05,000
+Fine";
+
+        assert!(SubRipParser::from(sub.as_bytes()).all(|sub| sub.is_ok()));
+
+        let results: Vec<_> = SubRipParser::from(sub.as_bytes()).strict(true).collect();
+        assert_eq!(3, results.len());
+
+        let err = results[0].as_ref().unwrap_err();
+        assert_eq!(ErrorKind::InvalidTimecode, err.kind());
+        assert_eq!(Some(2), err.line());
+        assert!(results[1].is_err());
+        assert_eq!(3, results[2].as_ref().unwrap().position);
+    }
+
+    #[test]
+    fn strict_recover() {
+        let sub = "\
+1
+00:00:05,000 --> 00:00:04,000
+Reversed
+
+2
+00:00:06,000 --> 00:00:07,000
+Good
+
+3
+00:00:08,000 --> 00:00:09,000
+Fine";
+
+        let results: Vec<_> = SubRipParser::from(sub.as_bytes())
+            .strict(true)
+            .recover(true)
+            .collect();
+
+        assert_eq!(3, results.len());
+        assert!(results[0].is_err());
+        assert_eq!(2, results[1].as_ref().unwrap().position);
+        assert_eq!(3, results[2].as_ref().unwrap().position);
+    }
+
+    #[test]
+    fn missing_positions() {
+        let sub = "\
+00:00:01,000 --> 00:00:02,000
+First
+
+00:00:02,000 --> 00:00:03,000
+Second
+
+7
+00:00:03,000 --> 00:00:04,000
+Seventh
+
+00:00:04,000 --> 00:00:05,000
+Eighth";
+
+        let positions: Vec<usize> = SubRipParser::from(sub.as_bytes())
+            .missing_positions(true)
+            .map(|sub| sub.unwrap().position)
+            .collect();
+        assert_eq!(vec![1, 2, 7, 8], positions);
+
+        assert!(SubRipParser::from(sub.as_bytes()).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn preserve_raw() {
+        let subtitle = "\
+1
+00:00:01,000 --> 00:00:02,000
+First line
+Second line
+
+2
+00:00:03,000 --> 00:00:04,000
+Last
+";
+        let mut parser = SubRipParser::from(subtitle.as_bytes()).preserve_raw(true);
+
+        let first = parser.next().unwrap().unwrap();
+        assert_eq!(
+            Some("1\n00:00:01,000 --> 00:00:02,000\nFirst line\nSecond line"),
+            parser.last_raw()
+        );
+        let last = parser.next().unwrap().unwrap();
+        assert_eq!(
+            Some("2\n00:00:03,000 --> 00:00:04,000\nLast"),
+            parser.last_raw()
+        );
+        assert!(parser.next().is_none());
+        assert_eq!(None, parser.last_raw());
+
+        // the same subtitle parsed without the raw lines is equal
+        let mut parser = SubRipParser::from(subtitle.as_bytes());
+        assert_eq!(first, parser.next().unwrap().unwrap());
+        assert_eq!(None, parser.last_raw());
+        assert_eq!(last, parser.next().unwrap().unwrap());
+    }
+
+    #[test]
+    fn blank_text_lines() {
+        let sub = "\
+1
+00:00:01,000 --> 00:00:02,000
+- First speaker
+
+- Second speaker
+
+
+2
+00:00:03,000 --> 00:00:04,000
+Next
+
+3
+Not a timecode
+";
+
+        let subs: Vec<SubRip> = SubRipParser::from(sub.as_bytes())
+            .blank_text_lines(true)
+            .map(|sub| sub.unwrap())
+            .collect();
+
+        assert_eq!(2, subs.len());
+        assert_eq!(
+            vec!["- First speaker", "", "- Second speaker"],
+            subs[0].text
+        );
+        assert_eq!(vec!["Next", "", "3", "Not a timecode"], subs[1].text);
+    }
+
+    #[test]
+    fn io_error() {
+        struct Failing;
+
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "disk failure",
+                ))
+            }
+        }
+
+        let err = SubRipParser::from(Failing).next().unwrap().unwrap_err();
+
+        assert_eq!(ErrorKind::Io, err.kind());
+        assert_eq!("line 1: io error: disk failure", err.to_string());
+    }
+
+    #[test]
+    fn no_read_ahead() {
+        /// A pipe that has no more input yet after `0`.
+        struct Pipe<'a>(&'a [u8]);
+
+        impl Read for Pipe<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0.is_empty() {
+                    panic!("read past the input available so far");
+                }
+                let len = self.0.len().min(buf.len());
+                buf[..len].copy_from_slice(&self.0[..len]);
+                self.0 = &self.0[len..];
+                Ok(len)
+            }
+        }
+
+        let subtitle = b"1\n00:00:01,000 --> 00:00:02,000\nHello\n\n";
+        let sub = SubRipParser::from(Pipe(subtitle)).next().unwrap().unwrap();
+
+        assert_eq!(vec!["Hello"], sub.text);
+    }
+
+    #[test]
+    fn recover() {
+        let sub = "\
+1
+00:00:00,000 --> 00:00:01,000
+First
+
+2
+00:00:01,000 -> -> oops
+4
+00:00:02,000 --> 00:00:03,000
+
+3
+00:00:03,000 --> 00:00:04,000
+Third
+
+4
+00:00:04,000 --> 00:00:05,000
+Fourth";
+
+        let results: Vec<_> = SubRipParser::from(sub.as_bytes()).recover(true).collect();
+
+        assert_eq!(4, results.len());
+        assert!(results[1].is_err());
+        let positions: Vec<usize> = results
+            .into_iter()
+            .filter_map(|sub| sub.ok())
+            .map(|sub| sub.position)
+            .collect();
+        assert_eq!(vec![1, 3, 4], positions);
+    }
+
     #[test]
     fn empty_lines() {
         let sub = "\
@@ -435,4 +1410,35 @@ test";
 
         assert_eq!(expected, parser.next().unwrap().unwrap());
     }
+
+    #[test]
+    fn separator_warnings() {
+        let sub = "\
+1
+00:00:00,000 --> 00:00:01,000
+test
+
+2
+00:00:01,000 --> 00:00:02,000
+test
+
+
+
+3
+00:00:02,000 --> 00:00:03,000
+test";
+
+        let mut parser = SubRipParser::from(sub.as_bytes());
+        assert_eq!(3, parser.by_ref().count());
+        assert!(parser.warnings().is_empty());
+
+        let mut parser = SubRipParser::from(sub.as_bytes()).check_separators(true);
+        assert_eq!(3, parser.by_ref().count());
+
+        let expected = vec![Warning::Separator {
+            position: 3,
+            blank_lines: 3,
+        }];
+        assert_eq!(expected, parser.warnings());
+    }
 }
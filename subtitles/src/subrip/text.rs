@@ -0,0 +1,247 @@
+use super::format::SubRip;
+use std::borrow::Cow;
+
+/// Abbreviations that end with a period without ending a sentence.
+const ABBREVIATIONS: &[&str] = &[
+    "mr.", "mrs.", "ms.", "dr.", "st.", "jr.", "sr.", "vs.", "prof.", "e.g.", "i.e.",
+];
+
+/// Join the text of consecutive subtitles into complete sentences.
+///
+/// Text is accumulated across subtitles until one ends with `.`, `?` or
+/// `!`. Ellipses and common abbreviations such as `Mr.` do not end a
+/// sentence. Trailing text without ending punctuation is returned as the
+/// last sentence.
+pub fn join_sentences<I: IntoIterator<Item = SubRip>>(subs: I) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+
+    for sub in subs {
+        for line in sub.text {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(line);
+        }
+
+        if ends_sentence(&current) {
+            sentences.push(current);
+            current = String::new();
+        }
+    }
+
+    if !current.is_empty() {
+        sentences.push(current);
+    }
+
+    sentences
+}
+
+/// Remove formatting tags from a line of subtitle text.
+///
+/// Both HTML-like tags such as `<i>` or `<font color="red">` and ASS
+/// override tags such as `{\b1}` are removed. A `<` or `{` without a
+/// matching closing bracket is kept as is.
+///
+/// `line` is borrowed back if it has no tags.
+pub fn strip_tags(line: &str) -> Cow<'_, str> {
+    let mut stripped = String::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find(&['<', '{'][..]) {
+        let close = if rest[start..].starts_with('<') {
+            '>'
+        } else {
+            '}'
+        };
+
+        match rest[start..].find(close) {
+            Some(len) => {
+                stripped.push_str(&rest[..start]);
+                rest = &rest[start + len + 1..];
+            }
+            None => break,
+        }
+    }
+    if rest.len() == line.len() {
+        return Cow::Borrowed(line);
+    }
+    stripped.push_str(rest);
+
+    Cow::Owned(stripped)
+}
+
+/// Decode HTML character references in a line of subtitle text.
+///
+/// The named references `&amp;`, `&lt;`, `&gt;`, `&quot;`, `&apos;` and
+/// `&nbsp;` are decoded, as are numeric ones such as `&#39;` and `&#x27;`.
+/// Anything else starting with `&` is kept as is.
+pub fn decode_entities(line: &str) -> String {
+    let mut decoded = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest
+            .find(';')
+            .and_then(|end| Some((decode_entity(&rest[1..end])?, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+/// Split HTML-like `markup` into lines of plain text.
+///
+/// Lines are broken at `<br>` tags, in any case and also written as
+/// `<br/>` or `<br />`, while newlines count as plain whitespace. Other
+/// tags are removed, entities are decoded, runs of whitespace are collapsed
+/// and empty lines are left out.
+pub(crate) fn markup_lines(markup: &str) -> Vec<String> {
+    // lowercasing ASCII keeps byte offsets, so they apply to `markup`
+    let lowercase = markup.to_ascii_lowercase();
+
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while let Some(br) = lowercase[start..].find("<br") {
+        lines.push(&markup[start..start + br]);
+        start = lowercase[start + br..]
+            .find('>')
+            .map_or(markup.len(), |len| start + br + len + 1);
+    }
+    lines.push(&markup[start..]);
+
+    lines
+        .into_iter()
+        .map(|line| {
+            decode_entities(&strip_tags(line))
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{A0}'),
+        _ => {
+            let number = name.strip_prefix('#')?;
+            let code = match number.strip_prefix(&['x', 'X'][..]) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+fn ends_sentence(text: &str) -> bool {
+    let text = text.trim_end_matches(&['"', '\'', ')', ']'][..]);
+
+    if text.ends_with("...") || text.ends_with('…') {
+        return false;
+    }
+    if !text.ends_with(&['.', '?', '!'][..]) {
+        return false;
+    }
+
+    let last_word = text.rsplit(' ').next().unwrap_or(text).to_lowercase();
+    !ABBREVIATIONS.contains(&last_word.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn sub(text: &str) -> SubRip {
+        SubRip::from_parts(1, Duration::ZERO, Duration::ZERO, text)
+    }
+
+    #[test]
+    fn join_split_sentence() {
+        let subs = vec![
+            sub("It's only after"),
+            sub("we've lost everything"),
+            sub("that we're free to do anything."),
+            sub("Right?"),
+        ];
+
+        let expected = vec![
+            "It's only after we've lost everything that we're free to do anything.",
+            "Right?",
+        ];
+
+        assert_eq!(expected, join_sentences(subs));
+    }
+
+    #[test]
+    fn ellipsis_and_abbreviation() {
+        let subs = vec![
+            sub("Wait..."),
+            sub("Mr."),
+            sub("Smith is here!"),
+            sub("And"),
+        ];
+
+        let expected = vec!["Wait... Mr. Smith is here!", "And"];
+
+        assert_eq!(expected, join_sentences(subs));
+    }
+
+    #[test]
+    fn strip_html_and_ass_tags() {
+        let line = r#"<i>Hello</i> {\b1}<font color="red">World</font>"#;
+        assert_eq!("Hello World", strip_tags(line));
+    }
+
+    #[test]
+    fn strip_unclosed_tag() {
+        assert_eq!("1 < 2 and {3", strip_tags("1 < 2 and {3"));
+    }
+
+    #[test]
+    fn strip_tags_borrows_untagged_lines() {
+        assert!(matches!(
+            strip_tags("No tags here"),
+            Cow::Borrowed("No tags here")
+        ));
+        assert!(matches!(strip_tags("<b>Bold</b>"), Cow::Owned(line) if line == "Bold"));
+    }
+
+    #[test]
+    fn decode_named_and_numeric_entities() {
+        let line = "Tom &amp; Jerry &lt;3 &quot;it&#39;s&#x21;&quot;";
+        assert_eq!("Tom & Jerry <3 \"it's!\"", decode_entities(line));
+    }
+
+    #[test]
+    fn keep_unknown_entities() {
+        let line = "AT&T; R&D &bogus; &#xZZ; &";
+        assert_eq!(line, decode_entities(line));
+    }
+}
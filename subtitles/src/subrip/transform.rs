@@ -0,0 +1,138 @@
+use super::format::{SubRip, Timecode};
+use std::time::Duration;
+
+/// Number subtitles sequentially from 1, in slice order.
+///
+/// Useful after removing subtitles, which leaves gaps in the positions.
+///
+/// # Example
+///
+/// ```
+/// let subtitle = "1\n00:00:01,000 --> 00:00:02,000\nKeep\n\n\
+///                 2\n00:00:02,000 --> 00:00:03,000\nDrop\n\n\
+///                 3\n00:00:03,000 --> 00:00:04,000\nKeep\n";
+///
+/// let mut subs: Vec<_> = subtitles::open(subtitle.as_bytes())
+///     .filter_map(Result::ok)
+///     .filter(|sub| sub.text[0] == "Keep")
+///     .collect();
+/// subtitles::renumber(&mut subs);
+///
+/// let positions: Vec<usize> = subs.iter().map(|sub| sub.position).collect();
+/// assert_eq!(vec![1, 2], positions);
+/// ```
+pub fn renumber(subs: &mut [SubRip]) {
+    for (index, sub) in subs.iter_mut().enumerate() {
+        sub.position = index + 1;
+    }
+}
+
+/// Keep at least `gap` between consecutive subtitles.
+///
+/// A subtitle ending less than `gap` before the next one starts is cut
+/// short, but never to before its own start.
+pub fn enforce_min_gap(subs: &mut [SubRip], gap: Duration) {
+    for index in 1..subs.len() {
+        let next_start = subs[index].start.as_duration();
+        let sub = &mut subs[index - 1];
+
+        if sub.end.as_duration() + gap > next_start {
+            let end = next_start.saturating_sub(gap).max(sub.start.as_duration());
+            sub.end = Timecode::from_duration(end);
+        }
+    }
+}
+
+/// Merge consecutive subtitles less than `gap` apart.
+///
+/// Overlapping subtitles are always merged. A merged subtitle spans from
+/// the earliest start to the latest end of its parts, and holds all their
+/// lines in order. The result is numbered sequentially from 1.
+pub fn merge_overlapping<I: IntoIterator<Item = SubRip>>(subs: I, gap: Duration) -> Vec<SubRip> {
+    let mut merged: Vec<SubRip> = Vec::new();
+
+    for sub in subs {
+        if let Some(last) = merged.last_mut() {
+            let apart = sub
+                .start
+                .as_duration()
+                .saturating_sub(last.end.as_duration());
+            if sub.start < last.end || apart < gap {
+                if sub.start < last.start {
+                    last.start = sub.start;
+                }
+                if sub.end > last.end {
+                    last.end = sub.end;
+                }
+                last.text.extend(sub.text);
+                continue;
+            }
+        }
+        merged.push(sub);
+    }
+
+    renumber(&mut merged);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(position: usize, start: u64, end: u64) -> SubRip {
+        SubRip::from_parts(
+            position,
+            Duration::from_millis(start),
+            Duration::from_millis(end),
+            "",
+        )
+    }
+
+    #[test]
+    fn renumber_positions() {
+        let mut subs = vec![sub(5, 0, 1), sub(9, 1, 2), sub(2, 2, 3)];
+        renumber(&mut subs);
+
+        let positions: Vec<usize> = subs.iter().map(|sub| sub.position).collect();
+        assert_eq!(vec![1, 2, 3], positions);
+    }
+
+    #[test]
+    fn merge() {
+        let mut first = sub(1, 0, 1000);
+        first.set_text("First");
+        let mut second = sub(2, 1000, 2000);
+        second.set_text("Second");
+        let mut third = sub(3, 2500, 3000);
+        third.set_text("Third");
+
+        let merged = merge_overlapping(vec![first, second, third], Duration::from_millis(100));
+
+        let mut expected = vec![sub(1, 0, 2000), sub(2, 2500, 3000)];
+        expected[0].set_text("First\nSecond");
+        expected[1].set_text("Third");
+        assert_eq!(expected, merged);
+    }
+
+    #[test]
+    fn merge_contained() {
+        let merged = merge_overlapping(vec![sub(4, 0, 3000), sub(7, 1000, 2000)], Duration::ZERO);
+        assert_eq!(vec![sub(1, 0, 3000)], merged);
+    }
+
+    #[test]
+    fn min_gap() {
+        let mut subs = vec![sub(1, 0, 1000), sub(2, 1050, 2000), sub(3, 2500, 3000)];
+        enforce_min_gap(&mut subs, Duration::from_millis(100));
+
+        let ends: Vec<Duration> = subs.iter().map(|sub| sub.end.as_duration()).collect();
+        assert_eq!(
+            vec![
+                Duration::from_millis(950),
+                Duration::from_millis(2000),
+                Duration::from_millis(3000),
+            ],
+            ends
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use super::format::{Precision, SubRip};
+use crate::webvtt::{self, Cue};
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE};
+use std::io::{self, Write};
+
+/// Write subtitles in SubRip (.srt) format.
+///
+/// Every subtitle is followed by a blank line.
+pub struct Writer<W: Write> {
+    writer: W,
+    precision: Precision,
+}
+
+impl<W: Write> Writer<W> {
+    /// Create a new writer with millisecond precision.
+    pub fn new(writer: W) -> Self {
+        Writer {
+            writer,
+            precision: Precision::Milliseconds,
+        }
+    }
+
+    /// Set the precision of the written timecodes.
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Write a single subtitle.
+    pub fn write(&mut self, sub: &SubRip) -> io::Result<()> {
+        write!(self.writer, "{}\n\n", sub.display_with(self.precision))
+    }
+
+    /// Write every subtitle in `subs`.
+    pub fn write_all<I: IntoIterator<Item = SubRip>>(&mut self, subs: I) -> io::Result<()> {
+        for sub in subs {
+            self.write(&sub)?;
+        }
+        self.writer.flush()
+    }
+}
+
+/// Write `subs` to `writer` in SubRip (.srt) format.
+pub fn write<W: Write, I: IntoIterator<Item = SubRip>>(writer: W, subs: I) -> io::Result<()> {
+    Writer::new(writer).write_all(subs)
+}
+
+/// Format `subs` as the contents of a SubRip (.srt) file.
+///
+/// The output is the same as [`write()`] would produce.
+pub fn to_string<I: IntoIterator<Item = SubRip>>(subs: I) -> String {
+    let mut out = Vec::new();
+    write(&mut out, subs).expect("writing to a Vec never fails");
+    String::from_utf8(out).expect("subtitles are valid UTF-8")
+}
+
+/// Format `subs` as a SubRip file written like `original`, which is in
+/// `encoding`: with the same line endings, and its BOM if it has one.
+pub(crate) fn encode_like<I: IntoIterator<Item = SubRip>>(
+    original: &[u8],
+    encoding: &'static Encoding,
+    subs: I,
+) -> Vec<u8> {
+    let (decoded, _) = encoding.decode_without_bom_handling(original);
+    let newline = match decoded.find(&['\r', '\n'][..]) {
+        Some(end) if decoded[end..].starts_with("\r\n") => "\r\n",
+        Some(end) if decoded[end..].starts_with('\r') => "\r",
+        _ => "\n",
+    };
+    let mut text = to_string(subs);
+    if newline != "\n" {
+        text = text.replace('\n', newline);
+    }
+
+    let mut contents = match Encoding::for_bom(original) {
+        Some((_, bom_len)) => original[..bom_len].to_vec(),
+        None => Vec::new(),
+    };
+    // encoding_rs only encodes to UTF-16 through these
+    if encoding == UTF_16LE {
+        contents.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+    } else if encoding == UTF_16BE {
+        contents.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+    } else {
+        contents.extend_from_slice(&encoding.encode(&text).0);
+    }
+    contents
+}
+
+/// Write `subs` to `writer` in LRC lyrics format.
+///
+/// Each subtitle becomes a `[mm:ss.xx]` tag with its start time, rounded
+/// to the nearest centisecond, followed by its lines joined by a space.
+/// Hours are carried into the minutes, as LRC has no hour field.
+pub fn to_lrc<W: Write, I: IntoIterator<Item = SubRip>>(mut writer: W, subs: I) -> io::Result<()> {
+    for sub in subs {
+        let centis = (sub.start.as_duration().as_millis() + 5) / 10;
+        writeln!(
+            writer,
+            "[{:02}:{:02}.{:02}]{}",
+            centis / 6000,
+            centis / 100 % 60,
+            centis % 100,
+            sub.text_joined(" ")
+        )?;
+    }
+    writer.flush()
+}
+
+/// Write `subs` to `writer` in WebVTT (.vtt) format.
+///
+/// A `WEBVTT` header is written first and every cue is followed by a
+/// blank line. Positions become the cue identifiers and the timecodes use
+/// `.` as the millisecond separator.
+pub fn to_webvtt<W: Write, I: IntoIterator<Item = SubRip>>(writer: W, subs: I) -> io::Result<()> {
+    webvtt::write(writer, subs.into_iter().map(Cue::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{format::Timecode, parser::SubRipParser},
+        *,
+    };
+
+    fn subtitle() -> SubRip {
+        SubRip {
+            position: 1,
+            start: Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                milliseconds: 456,
+            },
+            end: Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 59,
+                milliseconds: 995,
+            },
+            text: vec![String::from("Test")],
+        }
+    }
+
+    fn write_with(precision: Precision) -> String {
+        let mut out = Vec::new();
+        Writer::new(&mut out)
+            .precision(precision)
+            .write_all(vec![subtitle()])
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn milliseconds() {
+        let expected = "\
+1
+01:02:03,456 --> 01:02:59,995
+Test
+
+";
+        assert_eq!(expected, write_with(Precision::Milliseconds));
+    }
+
+    #[test]
+    fn centiseconds() {
+        let expected = "\
+1
+01:02:03.46 --> 01:03:00.00
+Test
+
+";
+        assert_eq!(expected, write_with(Precision::Centiseconds));
+    }
+
+    #[test]
+    fn seconds() {
+        let expected = "\
+1
+01:02:03 --> 01:03:00
+Test
+
+";
+        assert_eq!(expected, write_with(Precision::Seconds));
+    }
+
+    #[test]
+    fn round_trip() {
+        let subtitle = "\
+1
+00:00:01,000 --> 00:00:02,500
+First line
+Second line
+
+2
+00:00:03,000 --> 00:00:04,000
+Last
+
+";
+        let subs = SubRipParser::from(subtitle.as_bytes()).map(Result::unwrap);
+
+        let mut out = Vec::new();
+        write(&mut out, subs).unwrap();
+
+        assert_eq!(subtitle.as_bytes(), &out[..]);
+    }
+
+    #[test]
+    fn whole_file_to_string() {
+        let mut second = subtitle();
+        second.position = 2;
+        second.set_text("Two\nlines");
+
+        let expected = "\
+1
+01:02:03,456 --> 01:02:59,995
+Test
+
+2
+01:02:03,456 --> 01:02:59,995
+Two
+lines
+
+";
+        assert_eq!(expected, to_string(vec![subtitle(), second]));
+    }
+
+    #[test]
+    fn lrc() {
+        let second = SubRip {
+            position: 2,
+            start: Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 7,
+                milliseconds: 454,
+            },
+            end: Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 9,
+                milliseconds: 0,
+            },
+            text: vec![String::from("Second")],
+        };
+
+        let mut out = Vec::new();
+        to_lrc(&mut out, vec![subtitle(), second]).unwrap();
+
+        let expected = "\
+[62:03.46]Test
+[62:07.45]Second
+";
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn encode_like_original() {
+        let original = "\u{FEFF}1\r\n00:00:00,000 --> 00:00:01,000\r\nOld\r\n";
+        let expected = format!(
+            "\u{FEFF}{}",
+            to_string(vec![subtitle()]).replace('\n', "\r\n")
+        );
+        let actual = encode_like(original.as_bytes(), encoding_rs::UTF_8, vec![subtitle()]);
+        assert_eq!(expected.as_bytes(), &actual[..]);
+
+        let mut original = vec![0xFE, 0xFF];
+        original.extend("1\r00:00:00,000".encode_utf16().flat_map(u16::to_be_bytes));
+        let mut expected = vec![0xFE, 0xFF];
+        let text = to_string(vec![subtitle()]).replace('\n', "\r");
+        expected.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+        assert_eq!(expected, encode_like(&original, UTF_16BE, vec![subtitle()]));
+
+        let actual = encode_like(b"1\n", encoding_rs::WINDOWS_1252, vec![subtitle()]);
+        assert_eq!(to_string(vec![subtitle()]).as_bytes(), &actual[..]);
+    }
+
+    #[test]
+    fn webvtt() {
+        let mut second = subtitle();
+        second.position = 2;
+        second.set_text("Two\nlines");
+
+        let mut out = Vec::new();
+        to_webvtt(&mut out, vec![subtitle(), second]).unwrap();
+
+        let expected = "\
+WEBVTT
+
+1
+01:02:03.456 --> 01:02:59.995
+Test
+
+2
+01:02:03.456 --> 01:02:59.995
+Two
+lines
+
+";
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+    }
+}
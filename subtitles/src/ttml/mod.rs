@@ -0,0 +1,34 @@
+//! TTML (.ttml, .dfxp) subtitles.
+//!
+//! TTML documents are XML, with each subtitle a `<p>` element timed by its
+//! `begin` and `end` or `dur` attributes. Subtitles are parsed into
+//! [`SubRip`](crate::SubRip) subtitles.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! # use std::io::Error;
+//! use std::fs::File;
+//!
+//! let file = File::open("/path/to/subtitle.ttml")?;
+//! for subtitle in subtitles::ttml::open(file) {
+//!     match subtitle {
+//!         Ok(sub) => println!("{}", sub),
+//!         Err(err) => eprintln!("{}", err),
+//!     }
+//! }
+//! # Ok::<(), Error>(())
+//! ```
+
+mod parser;
+
+use std::io::Read;
+
+pub use parser::TtmlParser;
+
+/// Create a new parser for `subtitle`.
+///
+/// `subtitle` must be in TTML format.
+pub fn open<T: Read>(subtitle: T) -> TtmlParser<T> {
+    TtmlParser::from(subtitle)
+}
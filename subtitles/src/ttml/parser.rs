@@ -0,0 +1,289 @@
+use crate::{
+    error::{Error, ErrorKind},
+    lines::Lines,
+    subrip::{
+        format::{SubRip, Timecode},
+        markup_lines,
+    },
+};
+use std::{collections::VecDeque, error, io::Read, result, time::Duration};
+
+type Result<T> = result::Result<T, Box<dyn error::Error>>;
+type ParseResult<T> = result::Result<T, Error>;
+
+/// An iterator over the subtitles of a TTML file.
+///
+/// TTML is not line based, so the whole file is read on the first call to
+/// `next`. Errors are yielded first, in file order, and the subtitles are
+/// then sorted by start time and numbered sequentially from 1.
+///
+/// Times are read in the clock form, `HH:MM:SS` with optional fractional
+/// seconds, or as offsets in hours, minutes, seconds or milliseconds, such
+/// as `1.5s` or `250ms`. Frame and tick based times are not supported,
+/// and neither are times inherited from enclosing elements. `<br/>` breaks
+/// lines, and other tags such as `<span>` are removed.
+///
+/// This `struct` is created by [`open`](super::open).
+pub struct TtmlParser<T: Read> {
+    subtitle: Lines<T>,
+    read: bool,
+    errors: VecDeque<Error>,
+    cues: VecDeque<(Duration, Duration, Vec<String>)>,
+    position: usize,
+}
+
+impl<T: Read> TtmlParser<T> {
+    fn parse_next(&mut self) -> ParseResult<Option<SubRip>> {
+        if !self.read {
+            self.read_all();
+        }
+        if let Some(err) = self.errors.pop_front() {
+            return Err(err);
+        }
+
+        let (start, end, text) = match self.cues.pop_front() {
+            Some(cue) => cue,
+            None => return Ok(None),
+        };
+
+        self.position += 1;
+        Ok(Some(SubRip {
+            position: self.position,
+            start: Timecode::from_duration(start),
+            end: Timecode::from_duration(end),
+            text,
+        }))
+    }
+
+    fn read_all(&mut self) {
+        self.read = true;
+
+        let mut document = String::new();
+        loop {
+            match self.subtitle.next_line() {
+                Ok(Some(line)) => {
+                    document.push_str(&line);
+                    document.push('\n');
+                }
+                Ok(None) => break,
+                Err(err) => {
+                    self.errors.push_back(Error::new(ErrorKind::Io, err));
+                    break;
+                }
+            }
+        }
+
+        let mut rest = &document[..];
+        while let Some(open) = rest.find('<') {
+            rest = &rest[open..];
+            let name_len = rest[1..]
+                .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .map_or(rest.len() - 1, |len| len);
+            let name = &rest[1..1 + name_len];
+            let tag_len = match rest.find('>') {
+                Some(len) => len,
+                None => break,
+            };
+            let tag = &rest[..tag_len];
+            rest = &rest[tag_len + 1..];
+
+            // a self-closing paragraph has no text to show
+            if (name != "p" && !name.ends_with(":p")) || tag.ends_with('/') {
+                continue;
+            }
+
+            let close = format!("</{}>", name);
+            let content = match rest.find(&close) {
+                Some(len) => {
+                    let content = &rest[..len];
+                    rest = &rest[len + close.len()..];
+                    content
+                }
+                None => {
+                    self.errors.push_back(Error::new(
+                        ErrorKind::InvalidText,
+                        format!("unclosed paragraph `{}>`", tag),
+                    ));
+                    break;
+                }
+            };
+
+            match parse_timing(tag) {
+                Ok((start, end)) => self.cues.push_back((start, end, markup_lines(content))),
+                Err(err) => self
+                    .errors
+                    .push_back(Error::new(ErrorKind::InvalidTimecode, err)),
+            }
+        }
+        self.cues
+            .make_contiguous()
+            .sort_by_key(|(start, _, _)| *start);
+    }
+}
+
+/// Parse the `begin` and `end`, or `dur`, attributes of a `<p` tag.
+fn parse_timing(tag: &str) -> Result<(Duration, Duration)> {
+    let begin = attribute(tag, "begin").ok_or_else(|| format!("missing `begin` in `{}>`", tag))?;
+    let begin = parse_time(begin)?;
+
+    let end = match (attribute(tag, "end"), attribute(tag, "dur")) {
+        (Some(end), _) => parse_time(end)?,
+        (None, Some(dur)) => begin + parse_time(dur)?,
+        (None, None) => return Err(format!("missing `end` or `dur` in `{}>`", tag).into()),
+    };
+    Ok((begin, end))
+}
+
+/// Find the value of the attribute `name` in `tag`.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    tag.match_indices(name).find_map(|(index, _)| {
+        if !tag[..index].ends_with(char::is_whitespace) {
+            return None;
+        }
+        let value = tag[index + name.len()..]
+            .trim_start()
+            .strip_prefix('=')?
+            .trim_start();
+        let quote = value.chars().next().filter(|&c| c == '"' || c == '\'')?;
+        let value = &value[1..];
+        value.find(quote).map(|len| &value[..len])
+    })
+}
+
+/// Parse a time in the clock `HH:MM:SS.fff` form or the offset `1.5s` form.
+fn parse_time(time: &str) -> Result<Duration> {
+    let err = || format!("wrong time format `{}`", time);
+    let number = |number: &str| -> Result<f64> {
+        if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+            Ok(number.parse()?)
+        } else {
+            Err(err().into())
+        }
+    };
+
+    let millis = if time.contains(':') {
+        let fields: Vec<&str> = time.split(':').collect();
+        let (hours, minutes, seconds) = match fields[..] {
+            [hours, minutes, seconds] => (hours, minutes, seconds),
+            [_, _, _, _] => return Err(format!("frame times are not supported: `{}`", time).into()),
+            _ => return Err(err().into()),
+        };
+        let whole_seconds = seconds.split('.').next().unwrap_or_default();
+        if minutes.len() != 2 || whole_seconds.len() != 2 {
+            return Err(err().into());
+        }
+        let minutes = number(minutes)?;
+        let seconds = number(seconds)?;
+        if minutes >= 60.0 || seconds >= 60.0 {
+            return Err(err().into());
+        }
+        ((number(hours)? * 60.0 + minutes) * 60.0 + seconds) * 1000.0
+    } else {
+        let unit = time
+            .find(|c: char| c.is_ascii_alphabetic())
+            .ok_or_else(err)?;
+        let (value, unit) = time.split_at(unit);
+        let value = number(value)?;
+        match unit {
+            "h" => value * 3_600_000.0,
+            "m" => value * 60_000.0,
+            "s" => value * 1000.0,
+            "ms" => value,
+            "f" | "t" => {
+                return Err(format!("frame and tick times are not supported: `{}`", time).into())
+            }
+            _ => return Err(err().into()),
+        }
+    };
+    Ok(Duration::from_millis(millis.round() as u64))
+}
+
+impl<T: Read> From<T> for TtmlParser<T> {
+    fn from(subtitle: T) -> Self {
+        TtmlParser {
+            subtitle: Lines::new(subtitle),
+            read: false,
+            errors: VecDeque::new(),
+            cues: VecDeque::new(),
+            position: 0,
+        }
+    }
+}
+
+impl<T: Read> Iterator for TtmlParser<T> {
+    type Item = ParseResult<SubRip>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parse_next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_subtitles() {
+        let subtitle = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tt xmlns="http://www.w3.org/ns/ttml" xml:lang="en">
+  <body>
+    <div>
+      <p begin="00:00:01.000" end="00:00:02.500">Hello,<br/>
+        <span tts:fontStyle="italic">world</span>!</p>
+      <p xml:id="s2" begin="1:02:03.45" dur="1.5s">Tom &amp; Jerry</p>
+    </div>
+  </body>
+</tt>
+"#;
+        let mut parser = TtmlParser::from(subtitle.as_bytes());
+
+        let first = parser.next().unwrap().unwrap();
+        assert_eq!(1, first.position);
+        assert_eq!("00:00:01,000", first.start.to_string());
+        assert_eq!("00:00:02,500", first.end.to_string());
+        assert_eq!(vec!["Hello,", "world!"], first.text);
+
+        let second = parser.next().unwrap().unwrap();
+        assert_eq!(2, second.position);
+        assert_eq!("01:02:03,450", second.start.to_string());
+        assert_eq!("01:02:04,950", second.end.to_string());
+        assert_eq!(vec!["Tom & Jerry"], second.text);
+
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(
+            Duration::from_millis(3_723_004),
+            parse_time("01:02:03.004").unwrap()
+        );
+        assert_eq!(Duration::from_secs(3), parse_time("00:00:03").unwrap());
+        assert_eq!(Duration::from_millis(1100), parse_time("1.1s").unwrap());
+        assert_eq!(Duration::from_millis(250), parse_time("250ms").unwrap());
+        assert_eq!(Duration::from_secs(90), parse_time("1.5m").unwrap());
+        assert_eq!(Duration::from_secs(5400), parse_time("1.5h").unwrap());
+
+        assert!(parse_time("00:00:01:12").is_err());
+        assert!(parse_time("25f").is_err());
+        assert!(parse_time("00:0:01").is_err());
+        assert!(parse_time("00:00:60").is_err());
+        assert!(parse_time("soon").is_err());
+    }
+
+    #[test]
+    fn invalid_timing() {
+        let subtitle = r#"<tt><body><div>
+<p begin="later" end="00:00:02.000">Broken</p>
+<p end="00:00:02.000">No begin</p>
+<p begin="00:00:03.000" end="00:00:04.000">Fine</p>
+</div></body></tt>"#;
+        let mut parser = TtmlParser::from(subtitle.as_bytes());
+
+        let err = parser.next().unwrap().unwrap_err();
+        assert_eq!(ErrorKind::InvalidTimecode, err.kind());
+        assert!(parser.next().unwrap().is_err());
+        assert_eq!(vec!["Fine"], parser.next().unwrap().unwrap().text);
+        assert!(parser.next().is_none());
+    }
+}
@@ -0,0 +1,82 @@
+use crate::subrip::format::{SubRip, Timecode};
+use std::fmt;
+
+/// A single cue of a WebVTT (.vtt) file
+#[derive(Debug, PartialEq)]
+pub struct Cue {
+    /// Optional cue identifier
+    pub identifier: Option<String>,
+    /// The time that the cue should appear.
+    pub start: Timecode,
+    /// The time that the cue should disappear.
+    pub end: Timecode,
+    /// Cue settings such as `align:start`, as name and value pairs in the
+    /// order they appear.
+    pub settings: Vec<(String, String)>,
+    /// A list of lines in this cue.
+    pub text: Vec<String>,
+}
+
+impl From<SubRip> for Cue {
+    /// Convert a SubRip subtitle, using its position as the identifier.
+    fn from(sub: SubRip) -> Self {
+        Cue {
+            identifier: Some(sub.position.to_string()),
+            start: sub.start,
+            end: sub.end,
+            settings: Vec::new(),
+            text: sub.text,
+        }
+    }
+}
+
+impl fmt::Display for Cue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(identifier) = &self.identifier {
+            writeln!(f, "{}", identifier)?;
+        }
+
+        self.start.write_millis(f, '.')?;
+        f.write_str(" --> ")?;
+        self.end.write_millis(f, '.')?;
+        for (name, value) in &self.settings {
+            write!(f, " {}:{}", name, value)?;
+        }
+
+        write!(f, "\n{}", self.text.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_cue() {
+        let cue = Cue {
+            identifier: Some(String::from("intro")),
+            start: Timecode {
+                hours: 0,
+                minutes: 1,
+                seconds: 2,
+                milliseconds: 3,
+            },
+            end: Timecode {
+                hours: 0,
+                minutes: 1,
+                seconds: 4,
+                milliseconds: 500,
+            },
+            settings: vec![(String::from("align"), String::from("start"))],
+            text: vec![String::from("Hello"), String::from("World")],
+        };
+
+        let expected = "\
+intro
+00:01:02.003 --> 00:01:04.500 align:start
+Hello
+World";
+
+        assert_eq!(expected, format!("{}", cue));
+    }
+}
@@ -0,0 +1,87 @@
+//! WebVTT (.vtt) subtitles.
+//!
+//! # Usage
+//!
+//! ```no_run
+//! # use std::io::Error;
+//! use std::fs::File;
+//!
+//! let file = File::open("/path/to/subtitle.vtt")?;
+//!
+//! for cue in subtitles::webvtt::open(file) {
+//!     match cue {
+//!         Ok(cue) => println!("{}", cue),
+//!         Err(err) => eprintln!("{}", err),
+//!     }
+//! }
+//! # Ok::<(), Error>(())
+//! ```
+
+mod format;
+mod parser;
+
+use std::io::{self, Read, Write};
+
+pub use format::Cue;
+pub use parser::WebVttParser;
+
+/// Create a new parser for `subtitle`.
+///
+/// `subtitle` must be in WebVTT (.vtt) format. The header, comments and
+/// style and region blocks are skipped.
+pub fn open<T: Read>(subtitle: T) -> WebVttParser<T> {
+    WebVttParser::from(subtitle)
+}
+
+/// Write `cues` to `writer` in WebVTT (.vtt) format.
+///
+/// A `WEBVTT` header is written first and every cue is followed by a
+/// blank line.
+pub fn write<W: Write, I: IntoIterator<Item = Cue>>(mut writer: W, cues: I) -> io::Result<()> {
+    write!(writer, "WEBVTT\n\n")?;
+    for cue in cues {
+        write!(writer, "{}\n\n", cue)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let subtitle = "\
+WEBVTT
+Kind: captions
+
+intro
+00:00:01.000 --> 00:00:02.500 align:start position:10%
+Hello
+World
+
+00:00:03.000 --> 00:00:04.000
+Second
+";
+
+        let cues: Vec<Cue> = open(subtitle.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(2, cues.len());
+
+        let mut out = Vec::new();
+        write(&mut out, cues).unwrap();
+
+        let expected = "\
+WEBVTT
+
+intro
+00:00:01.000 --> 00:00:02.500 align:start position:10%
+Hello
+World
+
+00:00:03.000 --> 00:00:04.000
+Second
+
+";
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+    }
+}
@@ -0,0 +1,261 @@
+use super::format::Cue;
+use crate::{
+    error::{Error, ErrorKind},
+    lines::Lines,
+    subrip::{core::parse_time as parse_time_with_hours, format::Timecode},
+};
+use std::{error, io::Read, result};
+
+type Result<T> = result::Result<T, Box<dyn error::Error>>;
+type ParseResult<T> = result::Result<T, Error>;
+type Settings = Vec<(String, String)>;
+
+/// An iterator over the cues of a WebVTT (.vtt) file.
+///
+/// This `struct` is created by [`open`](super::open).
+pub struct WebVttParser<T: Read> {
+    subtitle: Lines<T>,
+}
+
+impl<T: Read> WebVttParser<T> {
+    fn parse_next(&mut self) -> ParseResult<Option<Cue>> {
+        loop {
+            let line = match self.skip_empty_lines() {
+                Ok(Some(line)) => line,
+                Ok(None) => return Ok(None),
+                Err(err) => return Err(Error::new(ErrorKind::Io, err)),
+            };
+
+            // header, comment, style and region blocks carry no cues
+            let is_cue = line.contains("-->")
+                || !["WEBVTT", "NOTE", "STYLE", "REGION"]
+                    .iter()
+                    .any(|keyword| line.starts_with(keyword));
+            if is_cue {
+                return self.parse_cue(line).map(Some);
+            }
+
+            self.skip_block()
+                .map_err(|err| Error::new(ErrorKind::Io, err))?;
+        }
+    }
+
+    fn parse_cue(&mut self, line: String) -> ParseResult<Cue> {
+        let (identifier, line) = if line.contains("-->") {
+            (None, line)
+        } else {
+            match self.next_line() {
+                Ok(Some(timing)) => (Some(line), timing),
+                Ok(None) => (Some(line), String::new()),
+                Err(err) => return Err(Error::new(ErrorKind::Io, err)),
+            }
+        };
+
+        let (start, end, settings) = match parse_timing(&line) {
+            Ok(timing) => timing,
+            Err(err) => {
+                let _ = self.skip_block();
+                return Err(Error::new(ErrorKind::InvalidTimecode, err));
+            }
+        };
+
+        let mut text = Vec::new();
+        loop {
+            match self.next_line() {
+                Ok(Some(line)) if !line.is_empty() => text.push(line),
+                Ok(_) => break,
+                Err(err) => return Err(Error::new(ErrorKind::Io, err)),
+            }
+        }
+
+        Ok(Cue {
+            identifier,
+            start,
+            end,
+            settings,
+            text,
+        })
+    }
+
+    fn skip_block(&mut self) -> Result<()> {
+        while let Some(line) = self.next_line()? {
+            if line.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_empty_lines(&mut self) -> Result<Option<String>> {
+        loop {
+            match self.next_line()? {
+                Some(line) => {
+                    if !line.is_empty() {
+                        break Ok(Some(line));
+                    }
+                }
+                None => break Ok(None),
+            }
+        }
+    }
+
+    fn next_line(&mut self) -> Result<Option<String>> {
+        Ok(self.subtitle.next_line()?)
+    }
+}
+
+/// Parse a timing line, `start --> end [settings]`.
+fn parse_timing(line: &str) -> Result<(Timecode, Timecode, Settings)> {
+    let mut parts = line.split_whitespace();
+    let start = parts.next().ok_or("missing start time")?;
+    if parts.next() != Some("-->") {
+        return Err("missing `-->` between start and end".into());
+    }
+    let end = parts.next().ok_or("missing end time")?;
+
+    let settings = parts
+        .map(|setting| match setting.find(':') {
+            Some(index) => (
+                String::from(&setting[..index]),
+                String::from(&setting[index + 1..]),
+            ),
+            None => (String::from(setting), String::new()),
+        })
+        .collect();
+
+    Ok((parse_time(start)?, parse_time(end)?, settings))
+}
+
+/// Parse a timestamp, `[hh:]mm:ss.ttt`.
+fn parse_time(time: &str) -> Result<Timecode> {
+    if time.matches(':').count() == 1 {
+        parse_time_with_hours(&format!("00:{}", time), &['.'])
+    } else {
+        parse_time_with_hours(time, &['.'])
+    }
+}
+
+impl<T: Read> From<T> for WebVttParser<T> {
+    fn from(subtitle: T) -> Self {
+        WebVttParser {
+            subtitle: Lines::new(subtitle),
+        }
+    }
+}
+
+impl<T: Read> Iterator for WebVttParser<T> {
+    type Item = ParseResult<Cue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parse_next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cues() {
+        let subtitle = "\
+WEBVTT - Example
+Kind: captions
+
+NOTE This is a comment
+
+STYLE
+::cue { color: yellow }
+
+intro
+00:01.000 --> 00:00:02.500 align:start position:10%
+Hello
+World
+
+00:00:03.000 --> 00:00:04.000
+Second";
+
+        let mut parser = WebVttParser::from(subtitle.as_bytes());
+
+        let expected = Cue {
+            identifier: Some(String::from("intro")),
+            start: Timecode {
+                hours: 0,
+                minutes: 0,
+                seconds: 1,
+                milliseconds: 0,
+            },
+            end: Timecode {
+                hours: 0,
+                minutes: 0,
+                seconds: 2,
+                milliseconds: 500,
+            },
+            settings: vec![
+                (String::from("align"), String::from("start")),
+                (String::from("position"), String::from("10%")),
+            ],
+            text: vec![String::from("Hello"), String::from("World")],
+        };
+        assert_eq!(expected, parser.next().unwrap().unwrap());
+
+        let expected = Cue {
+            identifier: None,
+            start: Timecode {
+                hours: 0,
+                minutes: 0,
+                seconds: 3,
+                milliseconds: 0,
+            },
+            end: Timecode {
+                hours: 0,
+                minutes: 0,
+                seconds: 4,
+                milliseconds: 0,
+            },
+            settings: Vec::new(),
+            text: vec![String::from("Second")],
+        };
+        assert_eq!(expected, parser.next().unwrap().unwrap());
+
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn invalid_cue() {
+        let subtitle = "\
+WEBVTT
+
+1
+00:00:01,000 --> 00:00:02,000
+Comma
+
+2
+00:00:03.000 --> 00:00:04.000
+Dot";
+
+        let mut parser = WebVttParser::from(subtitle.as_bytes());
+
+        assert!(parser.next().unwrap().is_err());
+        assert_eq!(
+            Some(String::from("2")),
+            parser.next().unwrap().unwrap().identifier
+        );
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn timestamps() {
+        let expected = Timecode {
+            hours: 0,
+            minutes: 1,
+            seconds: 2,
+            milliseconds: 500,
+        };
+        assert_eq!(expected, parse_time("01:02.500").unwrap());
+        assert_eq!(expected, parse_time("00:01:02.500").unwrap());
+
+        for time in &["00:01.5", "00:02.5000", "00:99.000", "00:02,500", "1:00:02"] {
+            assert!(parse_time(time).is_err(), "{}", time);
+        }
+    }
+}
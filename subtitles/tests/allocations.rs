@@ -0,0 +1,57 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::Write,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// The system allocator, counting allocations.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+#[test]
+fn allocations_per_subtitle() {
+    let count = 1000;
+    let mut file = String::new();
+    for i in 0..count {
+        write!(
+            file,
+            "{}\n00:00:01,000 --> 00:00:02,000\nSubtitle number {}\nsecond line\n\n",
+            i + 1,
+            i
+        )
+        .unwrap();
+    }
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let parsed = subtitles::open(file.as_bytes()).map(Result::unwrap).count();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    assert_eq!(count, parsed);
+    // 13 per subtitle when every line read allocated a fresh buffer
+    assert!(
+        allocations <= 7 * count + 10,
+        "{} allocations for {} subtitles",
+        allocations,
+        count
+    );
+}